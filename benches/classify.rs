@@ -0,0 +1,56 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Throughput of classifying a corpus of real world subjects
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use subject_classifier::{Classifier, Subject, SubjectRef};
+
+const CORPUS: &str = include_str!("subjects.txt");
+
+fn classify(c: &mut Criterion) {
+    let subjects: Vec<&str> = CORPUS.lines().collect();
+    let classifier = Classifier::default();
+
+    let mut group = c.benchmark_group("classify");
+    group.throughput(Throughput::Elements(subjects.len() as u64));
+    group.bench_function("Subject::from", |b| {
+        b.iter(|| {
+            for subject in &subjects {
+                black_box(Subject::from(*subject));
+            }
+        });
+    });
+    group.bench_function("SubjectRef::from", |b| {
+        b.iter(|| {
+            for subject in &subjects {
+                black_box(SubjectRef::from(*subject));
+            }
+        });
+    });
+    group.bench_function("classify_all", |b| {
+        b.iter(|| {
+            classifier
+                .classify_all(&subjects)
+                .for_each(|s| drop(black_box(s)))
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, classify);
+criterion_main!(benches);
@@ -0,0 +1,447 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Classify commit subjects read from stdin or a file
+//!
+//! ```sh
+//! git log --format='%h%x09%s' | subject-classifier --delimiter '\t' --field 2 --format tsv
+//! git log -z --format=%B | subject-classifier --null --format json
+//! ```
+//!
+//! As `commit-msg` hook, exits with 1 and prints a report if the message is rejected:
+//!
+//! ```sh
+//! #!/bin/sh
+//! exec subject-classifier --commit-msg "$1" --require-scope --max-length 72
+//! ```
+//!
+//! Types, rules, icons & the commit message policy are read from the first
+//! `.subject-classifier.toml` found in the current directory or its parents.
+
+use std::borrow::Cow;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, ValueEnum};
+use serde::Serialize;
+use subject_classifier::config::Config;
+use subject_classifier::icons::Theme;
+use subject_classifier::policy::{self, Policy, Violation};
+use subject_classifier::{Classifier, Subject, Type};
+
+/// Output format
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+enum Format {
+    /// Icon, scope & description, like a log viewer shows them
+    Plain,
+    /// Tab separated: kind, type, scope, breaking, icon, description
+    Tsv,
+    /// One JSON object per line
+    Json,
+    /// The subject normalized to `type(scope)!: description` where possible
+    Conventional,
+}
+
+/// Classify commit subjects
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Read from FILE instead of stdin
+    file: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Plain)]
+    format: Format,
+
+    /// Records are separated by NUL instead of newline and are complete commit messages, so
+    /// footers like `BREAKING CHANGE` are recognized (e.g. `git log -z --format=%B`)
+    #[arg(short = 'z', long)]
+    null: bool,
+
+    /// Split records into fields by DELIM, use `\t` for a tab
+    #[arg(short, long, value_name = "DELIM")]
+    delimiter: Option<String>,
+
+    /// The field containing the subject, other fields are passed through
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "delimiter"
+    )]
+    field: usize,
+
+    /// Icon theme: nerd-font-v2, nerd-font-v3, emoji, ascii or none, overrides the configuration
+    #[arg(long, value_name = "THEME")]
+    icon_theme: Option<Theme>,
+
+    /// Read the configuration from FILE instead of searching for `.subject-classifier.toml`
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Check the commit message in FILE like a `commit-msg` git hook
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["file", "null", "delimiter"])]
+    commit_msg: Option<PathBuf>,
+
+    /// With --commit-msg: conventional commits must have a scope, overrides the configuration
+    #[arg(long, requires = "commit_msg")]
+    require_scope: bool,
+
+    /// With --commit-msg: allow only TYPE, may be repeated, overrides the configuration
+    #[arg(long = "allow-type", value_name = "TYPE", requires = "commit_msg")]
+    allowed_types: Vec<String>,
+
+    /// With --commit-msg: maximum subject length in characters, overrides the configuration
+    #[arg(long, value_name = "N", requires = "commit_msg")]
+    max_length: Option<usize>,
+}
+
+/// A classified input record
+#[derive(Debug, Serialize)]
+struct Record<'a> {
+    #[serde(skip)]
+    subject: &'a Subject,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<&'a str>,
+    kind: &'static str,
+    #[serde(rename = "type")]
+    category: Option<&'a str>,
+    scope: Option<&'a str>,
+    breaking: bool,
+    icon: Cow<'a, str>,
+    description: &'a str,
+    text: &'a str,
+    pull_request: Option<&'a str>,
+}
+
+impl<'a> Record<'a> {
+    fn new(subject: &'a Subject, icon: Cow<'a, str>, fields: Vec<&'a str>) -> Self {
+        Self {
+            subject,
+            fields,
+            kind: subject.kind_name(),
+            category: subject.category().map(|c| c.as_str()),
+            scope: subject.scope().map(String::as_str),
+            breaking: subject.breaking_change(),
+            icon,
+            description: subject.description(),
+            text: subject.text(),
+            pull_request: subject.pull_request(),
+        }
+    }
+
+    fn write<W: Write>(&self, out: &mut W, format: Format) -> io::Result<()> {
+        match format {
+            Format::Plain => {
+                for field in &self.fields {
+                    write!(out, "{} ", field)?;
+                }
+                write!(out, "{}", self.icon)?;
+                if let Some(scope) = self.scope {
+                    write!(out, "{}: ", scope)?;
+                }
+                writeln!(out, "{}", self.subject.decorated())
+            }
+            Format::Tsv => {
+                let columns = [
+                    self.kind,
+                    self.category.unwrap_or_default(),
+                    self.scope.unwrap_or_default(),
+                    if self.breaking { "true" } else { "false" },
+                    &self.icon,
+                    self.description,
+                ];
+                let line: Vec<String> = self
+                    .fields
+                    .iter()
+                    .chain(&columns)
+                    .map(|c| c.replace(['\t', '\n'], " "))
+                    .collect();
+                writeln!(out, "{}", line.join("\t"))
+            }
+            Format::Conventional => {
+                for field in &self.fields {
+                    write!(out, "{} ", field)?;
+                }
+                writeln!(out, "{}", self.subject)
+            }
+            Format::Json => {
+                serde_json::to_writer(&mut *out, self)?;
+                writeln!(out)
+            }
+        }
+    }
+}
+
+/// Load the configuration, errors are prefixed with the offending file
+fn load_config(args: &Args) -> Result<Config, String> {
+    let path = match &args.config {
+        Some(path) => Some(path.clone()),
+        None => Config::find(&env::current_dir().map_err(|e| e.to_string())?, None),
+    };
+    let mut config = path.map_or_else(
+        || Ok(Config::default()),
+        |path| Config::load(&path).map_err(|e| format!("{}: {}", path.display(), e)),
+    )?;
+    if let Some(theme) = args.icon_theme {
+        config.theme = theme;
+    }
+    Ok(config)
+}
+
+fn run(args: &Args, config: &Config) -> io::Result<()> {
+    let input: Box<dyn BufRead> = match &args.file {
+        Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(File::open(path)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+    let delimiter = args.delimiter.as_deref().map(|d| d.replace("\\t", "\t"));
+    let separator = if args.null { b'\0' } else { b'\n' };
+    let index = args.field.saturating_sub(1);
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    for record in input.split(separator) {
+        let record = String::from_utf8_lossy(&record?).into_owned();
+        let record = record.trim_end_matches(['\r', '\n']);
+        let mut fields: Vec<&str> = match &delimiter {
+            Some(d) => record.split(d.as_str()).collect(),
+            None => vec![record],
+        };
+        let text = if index < fields.len() {
+            fields.remove(index)
+        } else {
+            ""
+        };
+        let subject = classify(&config.classifier, text, args.null);
+        Record::new(&subject, subject.icon_with(config), fields).write(&mut out, args.format)?;
+    }
+    out.flush()
+}
+
+/// Classify a subject line or, for NUL separated records, a complete commit message
+fn classify(classifier: &Classifier, text: &str, message: bool) -> Subject {
+    if message {
+        classifier.classify_message(text).subject
+    } else {
+        classifier.classify(text.lines().next().unwrap_or_default())
+    }
+}
+
+/// Write the report for a rejected commit message
+fn report<W: Write>(out: &mut W, subject: &str, violations: &[Violation]) -> io::Result<()> {
+    writeln!(out, "subject-classifier: commit message rejected\n")?;
+    writeln!(out, "    {}\n", subject)?;
+    for violation in violations {
+        writeln!(out, "  - {}", violation)?;
+    }
+    Ok(())
+}
+
+/// The configured policy with the command line overrides, fails on unknown types
+fn hook_policy(args: &Args, config: &Config) -> Result<Policy, String> {
+    let mut policy = config.policy.clone();
+    policy.require_scope |= args.require_scope;
+    if !args.allowed_types.is_empty() {
+        policy.allowed_types = args
+            .allowed_types
+            .iter()
+            .map(|name| match config.classifier.type_of(name) {
+                Type::Other => Err(format!("unknown type “{}” for --allow-type", name)),
+                category => Ok(category),
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    policy.max_length = args.max_length.or(policy.max_length);
+    Ok(policy)
+}
+
+/// Returns `false` if the commit message is rejected
+fn hook(policy: &Policy, config: &Config, path: &Path) -> io::Result<bool> {
+    let message = policy::strip_comments(&String::from_utf8_lossy(&fs::read(path)?));
+    let violations = policy.check_with(&message, &config.classifier);
+    if violations.is_empty() {
+        return Ok(true);
+    }
+    let subject = message.lines().next().unwrap_or_default();
+    report(&mut io::stderr().lock(), subject, &violations)?;
+    Ok(false)
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = match load_config(&args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("subject-classifier: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = &args.commit_msg {
+        let policy = match hook_policy(&args, &config) {
+            Ok(policy) => policy,
+            Err(e) => Args::command().error(ErrorKind::ValueValidation, e).exit(),
+        };
+        return match hook(&policy, &config, path) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("subject-classifier: {}: {}", path.display(), e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    match run(&args, &config) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("subject-classifier: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use subject_classifier::config::Config;
+    use subject_classifier::policy::Violation;
+    use subject_classifier::{Classifier, Subject, Type};
+
+    use crate::{classify, hook_policy, report, Args, Format, Record};
+
+    fn render(text: &str, fields: Vec<&str>, format: Format) -> String {
+        let subject = Subject::from(text);
+        let mut out = vec![];
+        Record::new(&subject, subject.icon().into(), fields)
+            .write(&mut out, format)
+            .expect("Writable");
+        String::from_utf8(out).expect("UTF-8")
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(
+            render("docs(readme): Foo", vec![], Format::Plain),
+            "✎ readme: Foo\n"
+        );
+        assert_eq!(
+            render("feat(ui)!: Foo", vec![], Format::Plain),
+            "⚠ ui: ! Foo\n"
+        );
+        assert_eq!(
+            render("Some commit", vec!["abc123"], Format::Plain),
+            "abc123   Some commit\n"
+        );
+    }
+
+    #[test]
+    fn tsv() {
+        assert_eq!(
+            render("feat(ui)!: Foo", vec!["abc123"], Format::Tsv),
+            "abc123\tconventional\tfeat\tui\ttrue\t⚠ \tFoo\n"
+        );
+        assert_eq!(
+            render("Merge #123", vec![], Format::Tsv),
+            "pull_request\t\t\tfalse\t\u{f407} \tMerge #123\n"
+        );
+    }
+
+    #[test]
+    fn conventional() {
+        assert_eq!(
+            render("Add a button", vec!["abc123"], Format::Conventional),
+            "abc123 feat: Add a button\n"
+        );
+        assert_eq!(
+            render("Merge #123", vec![], Format::Conventional),
+            "Merge #123\n"
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            render("docs(readme): Foo", vec![], Format::Json),
+            "{\"kind\":\"conventional\",\"type\":\"docs\",\"scope\":\"readme\",\"breaking\":false,\"icon\":\"✎ \",\"description\":\"Foo\",\"text\":\"docs(readme): Foo\",\"pull_request\":null}\n"
+        );
+        assert_eq!(
+            render("Remove Foo", vec!["abc123"], Format::Json),
+            "{\"fields\":[\"abc123\"],\"kind\":\"remove\",\"type\":null,\"scope\":null,\"breaking\":false,\"icon\":\"\u{f48e} \",\"description\":\"Remove Foo\",\"text\":\"Remove Foo\",\"pull_request\":null}\n"
+        );
+    }
+
+    #[test]
+    fn null_separated_messages() {
+        let classifier = Classifier::default();
+        let message = "feat: Foo\n\nBREAKING CHANGE: Bar\n";
+        assert!(classify(&classifier, message, true).breaking_change());
+        assert!(!classify(&classifier, message, false).breaking_change());
+
+        let message = "Merge branch 'foo' into 'main'\n\nSee merge request group/project!42\n";
+        assert_eq!(
+            classify(&classifier, message, true).pull_request(),
+            Some("42")
+        );
+    }
+
+    #[test]
+    fn allowed_types() {
+        let config = Config::default();
+        let args = |types: &[&str]| {
+            let mut argv = vec!["subject-classifier", "--commit-msg", "MSG"];
+            for name in types {
+                argv.extend(["--allow-type", name]);
+            }
+            Args::try_parse_from(argv).expect("Valid arguments")
+        };
+        let policy = hook_policy(&args(&["feat", "Fix"]), &config).expect("Known types");
+        assert_eq!(policy.allowed_types, vec![Type::Feat, Type::Fix]);
+        assert_eq!(
+            hook_policy(&args(&["feat", "fxi"]), &config).expect_err("Unknown type"),
+            "unknown type “fxi” for --allow-type"
+        );
+    }
+
+    #[test]
+    fn rejection_report() {
+        let mut out = vec![];
+        report(
+            &mut out,
+            "feat: Foo",
+            &[
+                Violation::MissingScope,
+                Violation::TooLong { length: 9, max: 5 },
+            ],
+        )
+        .expect("Writable");
+        assert_eq!(
+            String::from_utf8(out).expect("UTF-8"),
+            "subject-classifier: commit message rejected
+
+    feat: Foo
+
+  - missing scope
+  - subject is 9 characters long, at most 5 are allowed
+"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Generate a [Keep a Changelog](https://keepachangelog.com/en/1.0.0/) document from classified
+//! subjects.
+//!
+//! ```rust
+//! use subject_classifier::changelog::{Changelog, Metadata};
+//! use subject_classifier::Subject;
+//!
+//! // Newest first, like `git log`
+//! let log = [
+//!     ("feat: Add dark mode", "2022-10-10"),
+//!     ("Release v0.2.0", "2022-10-09"),
+//!     ("Fix crash on startup", "2022-10-08"),
+//!     ("ci: Cache registry", "2022-10-07"),
+//! ];
+//! let changelog = Changelog::new(log.iter().map(|(subject, date)| {
+//!     let metadata = Metadata {
+//!         date: Some((*date).to_owned()),
+//!         ..Metadata::default()
+//!     };
+//!     (Subject::from(*subject), metadata)
+//! }));
+//! let text = changelog.to_string();
+//! assert!(text.contains("## [Unreleased]\n\n### Added\n\n- Add dark mode\n"));
+//! assert!(text.contains("## [0.2.0] - 2022-10-09\n\n### Fixed\n\n- Fix crash on startup\n"));
+//! ```
+
+use std::fmt;
+
+use crate::{Subject, Type};
+
+/// Additional information about a commit
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Metadata {
+    /// Commit id
+    pub id: Option<String>,
+    /// Author of the commit
+    pub author: Option<String>,
+    /// Commit date, used as release date for [`Subject::Release`] commits
+    pub date: Option<String>,
+}
+
+/// A Keep a Changelog section, ordered as recommended by Keep a Changelog
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Section {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl Section {
+    /// All sections in the order they are rendered
+    pub const ALL: [Self; 6] = [
+        Self::Added,
+        Self::Changed,
+        Self::Deprecated,
+        Self::Removed,
+        Self::Fixed,
+        Self::Security,
+    ];
+
+    /// The section a subject belongs to, `None` if the subject is not notable
+    ///
+    /// Merges, releases, fixups, subtree operations and maintenance types like
+    /// [`Type::Chore`] or [`Type::Ci`] are not notable.
+    #[must_use]
+    #[inline]
+    pub const fn of(subject: &Subject) -> Option<Self> {
+        match subject {
+            Subject::ConventionalCommit { category, .. } => match category {
+                Type::Feat => Some(Self::Added),
+                Type::Change | Type::Improvement | Type::Perf | Type::Deps | Type::I18n => {
+                    Some(Self::Changed)
+                }
+                Type::Deprecate => Some(Self::Deprecated),
+                Type::Fix => Some(Self::Fixed),
+                Type::Security => Some(Self::Security),
+                Type::Archive
+                | Type::Build
+                | Type::Chore
+                | Type::Ci
+                | Type::Dev
+                | Type::Docs
+                | Type::Issue
+                | Type::Other
+                | Type::Refactor
+                | Type::Repo
+                | Type::Style
+                | Type::Test
+                | Type::Custom(_) => None,
+            },
+            Subject::Remove { .. } => Some(Self::Removed),
+            Subject::Rename { .. } | Subject::Revert { .. } => Some(Self::Changed),
+            Subject::Fixup { .. }
+            | Subject::Merge { .. }
+            | Subject::PullRequest { .. }
+            | Subject::Release { .. }
+            | Subject::SubtreeCommit { .. }
+            | Subject::Simple { .. } => None,
+        }
+    }
+
+    /// The section heading
+    #[must_use]
+    #[inline]
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::Added => "Added",
+            Self::Changed => "Changed",
+            Self::Deprecated => "Deprecated",
+            Self::Removed => "Removed",
+            Self::Fixed => "Fixed",
+            Self::Security => "Security",
+        }
+    }
+}
+
+/// A notable change
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change {
+    /// The section the change is listed in
+    pub section: Section,
+    /// The classified subject
+    pub subject: Subject,
+    /// Additional commit information
+    pub metadata: Metadata,
+}
+
+/// All notable changes of a release
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Release {
+    /// Released version, `None` for unreleased changes
+    pub version: Option<String>,
+    /// Release date
+    pub date: Option<String>,
+    /// Notable changes, newest first
+    pub changes: Vec<Change>,
+}
+
+impl Release {
+    /// The changes listed in `section`
+    #[inline]
+    pub fn section(&self, section: Section) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(move |c| c.section == section)
+    }
+}
+
+impl fmt::Display for Release {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.version, &self.date) {
+            (Some(version), Some(date)) => writeln!(f, "## [{}] - {}", version, date)?,
+            (Some(version), None) => writeln!(f, "## [{}]", version)?,
+            (None, _) => writeln!(f, "## [Unreleased]")?,
+        }
+        for section in Section::ALL {
+            let mut changes = self.section(section).peekable();
+            if changes.peek().is_none() {
+                continue;
+            }
+            write!(f, "\n### {}\n\n", section.title())?;
+            for change in changes {
+                let description = change.subject.decorated();
+                match change.subject.scope() {
+                    Some(scope) => writeln!(f, "- {}: {}", scope, description)?,
+                    None => writeln!(f, "- {}", description)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A changelog grouped by releases
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Changelog {
+    /// Changes since the last release
+    pub unreleased: Release,
+    /// Releases, newest first
+    pub releases: Vec<Release>,
+}
+
+impl Changelog {
+    /// Create a changelog from entries ordered newest first, like `git log` outputs them
+    ///
+    /// Every [`Subject::Release`] starts a new release containing the older entries.
+    #[must_use]
+    #[inline]
+    pub fn new<I>(entries: I) -> Self
+    where
+        I: IntoIterator<Item = (Subject, Metadata)>,
+    {
+        let mut result = Self::default();
+        for (subject, metadata) in entries {
+            if let Subject::Release { version, .. } = &subject {
+                result.releases.push(Release {
+                    version: Some(version.clone()),
+                    date: metadata.date,
+                    changes: vec![],
+                });
+            } else if let Some(section) = Section::of(&subject) {
+                let release = result.releases.last_mut().unwrap_or(&mut result.unreleased);
+                release.changes.push(Change {
+                    section,
+                    subject,
+                    metadata,
+                });
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for Changelog {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+
+",
+        )?;
+        write!(f, "{}", self.unreleased)?;
+        for release in &self.releases {
+            write!(f, "\n{}", release)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Changelog, Metadata, Section};
+    use crate::Subject;
+
+    fn changelog(log: &[&str]) -> Changelog {
+        Changelog::new(log.iter().map(|s| (Subject::from(*s), Metadata::default())))
+    }
+
+    #[test]
+    fn sections() {
+        for (text, section) in [
+            ("feat: Foo", Some(Section::Added)),
+            ("Add Foo", Some(Section::Added)),
+            ("perf: Foo", Some(Section::Changed)),
+            ("Rename Foo", Some(Section::Changed)),
+            ("Deprecate Foo", Some(Section::Deprecated)),
+            ("Remove Foo", Some(Section::Removed)),
+            ("fix: Foo", Some(Section::Fixed)),
+            ("security: Foo", Some(Section::Security)),
+            ("chore: Foo", None),
+            ("Merge #123", None),
+            ("Some commit", None),
+        ] {
+            assert_eq!(Section::of(&Subject::from(text)), section, "{}", text);
+        }
+    }
+
+    #[test]
+    fn releases() {
+        let result = changelog(&[
+            "feat: Unreleased",
+            "Release v0.2.0",
+            "fix(ui): Second",
+            "ci: Ignored",
+            "Release foo@v0.1.0",
+            "Remove Foo",
+        ]);
+        assert_eq!(result.unreleased.changes.len(), 1);
+        assert_eq!(result.releases.len(), 2);
+        assert_eq!(result.releases[0].version.as_deref(), Some("0.2.0"));
+        assert_eq!(result.releases[1].version.as_deref(), Some("0.1.0"));
+        assert_eq!(result.releases[1].changes[0].section, Section::Removed);
+    }
+
+    #[test]
+    fn render() {
+        let mut result = changelog(&[
+            "Release v0.2.0",
+            "hotfix(ui): Align icon",
+            "feat: Foo",
+            "security: Fix CSV-FOO-1234",
+            "Deprecate Bar",
+            "feat: Bar",
+        ]);
+        result.releases[0].date = Some("2022-10-09".to_owned());
+        let text = result.to_string();
+        let expected = "## [Unreleased]
+
+## [0.2.0] - 2022-10-09
+
+### Added
+
+- Foo
+- Bar
+
+### Deprecated
+
+- Deprecate Bar
+
+### Fixed
+
+- ui: Align icon
+
+### Security
+
+- Fix CSV-FOO-1234
+";
+        assert!(text.starts_with("# Changelog\n"));
+        assert!(text.ends_with(expected), "{}", text);
+    }
+}
@@ -0,0 +1,758 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Configurable classification
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::RegexSet;
+
+use crate::rules::{self, Derivation, Rule};
+use crate::subject_ref::{split_squash, Span};
+use crate::validate::validate_with;
+use crate::{CommitMessage, Diagnostic, Subject, SubjectKind, SubjectRef, Type, TYPES};
+
+/// The classifier used by [`Subject::from`]
+pub(crate) static DEFAULT: Lazy<Classifier> = Lazy::new(Classifier::default);
+
+/// Why a subject has no structured form, see [`Classifier::try_classify`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClassifyError {
+    /// The subject is empty or consists of whitespace only
+    Empty,
+    /// The subject starts like a conventional commit, but the type is unknown
+    UnknownType(String),
+    /// No rule recognized the subject
+    Unclassified,
+}
+
+impl fmt::Display for ClassifyError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty subject"),
+            Self::UnknownType(word) => write!(f, "unknown type “{}”", word),
+            Self::Unclassified => f.write_str("subject does not match any known format"),
+        }
+    }
+}
+
+impl std::error::Error for ClassifyError {}
+
+/// A classification and how it was derived, see [`Classifier::explain`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Classification<'a> {
+    /// The classified subject
+    pub subject: SubjectRef<'a>,
+    /// Name of the rule which classified the subject, `None` if no rule applied
+    pub rule: Option<&'static str>,
+    /// How the classification was derived
+    pub derivation: Derivation,
+}
+
+impl<'a> Classification<'a> {
+    fn fallback(subject: &'a str) -> Self {
+        Self {
+            subject: SubjectRef::new(subject, SubjectKind::Simple),
+            rule: None,
+            derivation: Derivation::Fallback,
+        }
+    }
+
+    fn with_squash(self, text: &'a str, squash: Option<Span>) -> Self {
+        Self {
+            subject: self.subject.with_squash(text, squash),
+            ..self
+        }
+    }
+
+    /// Confidence in the classification between `0.0` and `1.0`, see [`Derivation::confidence`]
+    #[must_use]
+    #[inline]
+    pub const fn confidence(&self) -> f32 {
+        self.derivation.confidence()
+    }
+}
+
+/// Classifies subjects using a configurable table of conventional commit types and an ordered
+/// pipeline of [`Rule`]s
+///
+/// The default classifier is the one used by [`Subject::from`]. Use
+/// [`Classifier::builder`] to teach it additional words or rules.
+///
+/// ```rust
+/// use subject_classifier::{Classifier, Subject, Type};
+///
+/// let classifier = Classifier::builder()
+///     .alias("infra", Type::Build)
+///     .category("ops")
+///     .build();
+/// let subject = classifier.classify("infra: Provision new runners");
+/// assert_eq!(
+///     subject,
+///     Subject::ConventionalCommit {
+///         breaking_change: false,
+///         category: Type::Build,
+///         scope: None,
+///         description: "Provision new runners".to_owned(),
+///         text: "infra: Provision new runners".to_owned(),
+///         pull_request: None,
+///     }
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Classifier {
+    types: HashMap<String, Type>,
+    rules: Vec<Arc<dyn Rule>>,
+    /// The [`Rule::pattern`]s of all rules
+    prefilter: RegexSet,
+    /// Index of each rule's pattern in `prefilter`
+    patterns: Vec<Option<usize>>,
+}
+
+impl Default for Classifier {
+    #[inline]
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Classifier {
+    /// Returns a builder starting with the default type table and rules
+    #[must_use]
+    #[inline]
+    pub fn builder() -> ClassifierBuilder {
+        let types = TYPES
+            .iter()
+            .map(|(word, category)| ((*word).to_owned(), category.clone()))
+            .collect();
+        ClassifierBuilder {
+            types,
+            rules: rules::defaults(),
+        }
+    }
+
+    /// Names of the rules in the order they are tried
+    #[inline]
+    pub fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// Map a conventional commit type word to a [`Type`], `Type::Other` if unknown
+    ///
+    /// Words are matched case-insensitive.
+    #[must_use]
+    #[inline]
+    pub fn type_of(&self, word: &str) -> Type {
+        self.lookup(word).cloned().unwrap_or(Type::Other)
+    }
+
+    /// The table keys are lowercase, only words with uppercase letters need to be converted
+    fn lookup(&self, word: &str) -> Option<&Type> {
+        self.types.get(word).or_else(|| {
+            if word.chars().any(char::is_uppercase) {
+                self.types.get(&word.to_lowercase())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Classify a commit subject
+    #[must_use]
+    #[inline]
+    pub fn classify(&self, subject: &str) -> Subject {
+        self.classify_ref(subject).to_subject()
+    }
+
+    /// Classify a commit subject without copying it, see [`SubjectRef`]
+    #[must_use]
+    #[inline]
+    pub fn classify_ref<'a>(&self, subject: &'a str) -> SubjectRef<'a> {
+        let (text, squash) = split_squash(subject);
+        self.candidates(text)
+            .find_map(|rule| rule.classify(text, self))
+            .unwrap_or_else(|| SubjectRef::new(text, SubjectKind::Simple))
+            .with_squash(subject, squash)
+    }
+
+    /// Classify a commit subject and report how the result was derived
+    ///
+    /// ```rust
+    /// use subject_classifier::rules::Derivation;
+    /// use subject_classifier::Classifier;
+    ///
+    /// let classifier = Classifier::default();
+    /// assert_eq!(classifier.explain("feat: Foo").derivation, Derivation::Explicit);
+    /// let result = classifier.explain("Add Foo");
+    /// assert_eq!(result.derivation, Derivation::Keyword);
+    /// assert_eq!(result.rule, Some("add"));
+    /// assert!(result.confidence() < 1.0);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn explain<'a>(&self, subject: &'a str) -> Classification<'a> {
+        let (text, squash) = split_squash(subject);
+        self.candidates(text)
+            .find_map(|rule| self.apply(rule.as_ref(), text))
+            .unwrap_or_else(|| Classification::fallback(text))
+            .with_squash(subject, squash)
+    }
+
+    /// Every classification of a commit subject, the most confident first
+    ///
+    /// Each rule applying to `subject` contributes one classification, rules with the same
+    /// confidence keep their pipeline order. Contains just a [`SubjectKind::Simple`] fallback
+    /// if no rule applies.
+    ///
+    /// ```rust
+    /// use subject_classifier::{Classifier, SubjectKind};
+    ///
+    /// let alternatives = Classifier::default().alternatives("Revert foo");
+    /// assert_eq!(alternatives[0].subject.kind(), &SubjectKind::Revert);
+    /// assert!(alternatives[1].subject.category().is_some());
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn alternatives<'a>(&self, subject: &'a str) -> Vec<Classification<'a>> {
+        let (text, squash) = split_squash(subject);
+        let mut result: Vec<Classification<'a>> = self
+            .candidates(text)
+            .filter_map(|rule| self.apply(rule.as_ref(), text))
+            .map(|c| c.with_squash(subject, squash.clone()))
+            .collect();
+        if result.is_empty() {
+            result.push(Classification::fallback(text).with_squash(subject, squash));
+        }
+        result.sort_by_key(|c| Reverse(c.derivation));
+        result
+    }
+
+    /// The rules whose pattern matches `subject`, in pipeline order
+    fn candidates<'s>(&'s self, subject: &str) -> impl Iterator<Item = &'s Arc<dyn Rule>> + 's {
+        let matches = self.prefilter.matches(subject);
+        self.rules
+            .iter()
+            .zip(&self.patterns)
+            .filter(move |(_, pattern)| pattern.is_none_or(|i| matches.matched(i)))
+            .map(|(rule, _)| rule)
+    }
+
+    fn apply<'a>(&self, rule: &dyn Rule, subject: &'a str) -> Option<Classification<'a>> {
+        let result = rule.classify(subject, self)?;
+        Some(Classification {
+            derivation: rule.derivation(&result),
+            rule: Some(rule.name()),
+            subject: result,
+        })
+    }
+
+    /// Classify a commit subject, rejecting subjects without a structured form
+    ///
+    /// Where [`Classifier::classify`] falls back to [`Subject::Simple`] or a conventional commit
+    /// of [`Type::Other`], this returns the reason.
+    ///
+    /// ```rust
+    /// use subject_classifier::{Classifier, ClassifyError};
+    ///
+    /// let classifier = Classifier::default();
+    /// assert!(classifier.try_classify("feat: Foo").is_ok());
+    /// assert_eq!(
+    ///     classifier.try_classify("Makefile: Fix typo"),
+    ///     Err(ClassifyError::UnknownType("Makefile".to_owned()))
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClassifyError`] if `subject` is empty or no rule recognized it.
+    #[inline]
+    pub fn try_classify(&self, subject: &str) -> Result<Subject, ClassifyError> {
+        self.try_classify_ref(subject).map(|s| s.to_subject())
+    }
+
+    /// Like [`Classifier::try_classify`], but without copying the subject
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClassifyError`] if `subject` is empty or no rule recognized it.
+    #[inline]
+    pub fn try_classify_ref<'a>(&self, subject: &'a str) -> Result<SubjectRef<'a>, ClassifyError> {
+        if subject.trim().is_empty() {
+            return Err(ClassifyError::Empty);
+        }
+        let result = self.classify_ref(subject);
+        match result.kind() {
+            SubjectKind::Simple => Err(ClassifyError::Unclassified),
+            SubjectKind::ConventionalCommit {
+                category: Type::Other,
+                ..
+            } => Err(
+                rules::type_prefix(subject).map_or(ClassifyError::Unclassified, |word| {
+                    ClassifyError::UnknownType(word.to_owned())
+                }),
+            ),
+            _ => Ok(result),
+        }
+    }
+
+    /// Classify many subjects
+    ///
+    /// All rule patterns are matched in a single pass over each subject, only the rules
+    /// with a matching pattern are tried.
+    ///
+    /// ```rust
+    /// use subject_classifier::{Classifier, Subject};
+    ///
+    /// let log = "feat: Foo\nRelease v1.0.0\nfixup! feat: Foo";
+    /// let subjects: Vec<Subject> = Classifier::default().classify_all(log.lines()).collect();
+    /// assert!(matches!(subjects[1], Subject::Release { .. }));
+    /// ```
+    #[inline]
+    pub fn classify_all<'c, I>(&'c self, subjects: I) -> impl Iterator<Item = Subject> + 'c
+    where
+        I: IntoIterator + 'c,
+        I::Item: AsRef<str>,
+    {
+        subjects
+            .into_iter()
+            .map(move |subject| self.classify(subject.as_ref()))
+    }
+
+    /// Classify a complete commit message, see [`CommitMessage`]
+    #[must_use]
+    #[inline]
+    pub fn classify_message(&self, message: &str) -> CommitMessage {
+        CommitMessage::parse(message, self)
+    }
+
+    /// Validate a subject line like [`validate`](crate::validate()), but using this
+    /// classifier's type table
+    #[must_use]
+    #[inline]
+    pub fn validate(&self, subject: &str) -> Vec<Diagnostic> {
+        validate_with(
+            subject,
+            |word| self.lookup(word).is_some(),
+            || {
+                let mut words: Vec<String> = self
+                    .types
+                    .keys()
+                    .filter(|w| !w.contains(' '))
+                    .cloned()
+                    .collect();
+                words.sort();
+                words
+            },
+        )
+    }
+}
+
+/// Builder for a [`Classifier`]
+#[derive(Clone, Debug)]
+pub struct ClassifierBuilder {
+    types: HashMap<String, Type>,
+    rules: Vec<Arc<dyn Rule>>,
+}
+
+impl ClassifierBuilder {
+    /// Recognize `word` as `category`, replacing any existing mapping of `word`
+    #[must_use]
+    #[inline]
+    pub fn alias(mut self, word: &str, category: Type) -> Self {
+        self.types.insert(word.to_lowercase(), category);
+        self
+    }
+
+    /// Stop recognizing `word` as a type
+    #[must_use]
+    #[inline]
+    pub fn remove_alias(mut self, word: &str) -> Self {
+        self.types.remove(&word.to_lowercase());
+        self
+    }
+
+    /// Register a custom category recognized by the word `name`
+    ///
+    /// Subjects prefixed with `name` are classified as `Type::Custom(name)`.
+    #[must_use]
+    #[inline]
+    pub fn category(self, name: &str) -> Self {
+        let category = Type::Custom(name.to_owned());
+        self.alias(name, category)
+    }
+
+    /// Forget all types, including the default ones
+    #[must_use]
+    #[inline]
+    pub fn clear(mut self) -> Self {
+        self.types.clear();
+        self
+    }
+
+    /// Append a rule, it is tried after all other rules
+    #[must_use]
+    #[inline]
+    pub fn push_rule<R: Rule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// Insert a rule at position `index` of the pipeline
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of rules.
+    #[must_use]
+    #[inline]
+    pub fn insert_rule<R: Rule + 'static>(mut self, index: usize, rule: R) -> Self {
+        self.rules.insert(index, Arc::new(rule));
+        self
+    }
+
+    /// Insert a rule directly before the rule named `name`
+    ///
+    /// Appends the rule if there is no rule named `name`.
+    #[must_use]
+    #[inline]
+    pub fn insert_rule_before<R: Rule + 'static>(mut self, name: &str, rule: R) -> Self {
+        let index = self
+            .rules
+            .iter()
+            .position(|r| r.name() == name)
+            .unwrap_or(self.rules.len());
+        self.rules.insert(index, Arc::new(rule));
+        self
+    }
+
+    /// Remove the rule named `name`
+    #[must_use]
+    #[inline]
+    pub fn remove_rule(mut self, name: &str) -> Self {
+        self.rules.retain(|r| r.name() != name);
+        self
+    }
+
+    /// Replace the whole pipeline, rules are tried in the given order
+    #[must_use]
+    #[inline]
+    pub fn rules(mut self, rules: Vec<Arc<dyn Rule>>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Build the classifier
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [`Rule::pattern`] is not a valid regular expression.
+    #[must_use]
+    #[inline]
+    pub fn build(self) -> Classifier {
+        let mut sources = vec![];
+        let patterns = self
+            .rules
+            .iter()
+            .map(|rule| {
+                rule.pattern().map(|pattern| {
+                    sources.push(pattern);
+                    sources.len() - 1
+                })
+            })
+            .collect();
+        let prefilter = RegexSet::new(sources).expect("Valid Regex");
+        Classifier {
+            types: self.types,
+            rules: self.rules,
+            prefilter,
+            patterns,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::rules::{self, Derivation, Rule};
+    use crate::subject_ref::split_squash;
+    use crate::{
+        Classifier, ClassifyError, DiagnosticKind, Subject, SubjectKind, SubjectRef, Type,
+    };
+
+    #[test]
+    fn default() {
+        let classifier = Classifier::default();
+        for text in [
+            "feature: Foo",
+            "hotfix(ui): Foo",
+            "Makefile: replace '-' in plugins_var",
+            "Merged PR 36587: Add Foo calibration to item type",
+            "Release v2.11.0",
+        ] {
+            assert_eq!(classifier.classify(text), Subject::from(text));
+        }
+    }
+
+    #[test]
+    fn alias() {
+        let classifier = Classifier::builder().alias("infra", Type::Build).build();
+        assert_eq!(
+            classifier.classify("INFRA(ci): Add runners"),
+            Subject::ConventionalCommit {
+                breaking_change: false,
+                category: Type::Build,
+                scope: Some("ci".to_owned()),
+                description: "Add runners".to_owned(),
+                text: "INFRA(ci): Add runners".to_owned(),
+                pull_request: None,
+            }
+        );
+    }
+
+    #[test]
+    fn remap() {
+        let classifier = Classifier::builder()
+            .alias("done", Type::Chore)
+            .remove_alias("gi")
+            .build();
+        assert_eq!(classifier.type_of("done"), Type::Chore);
+        assert_eq!(classifier.type_of("gi"), Type::Other);
+        assert_eq!(classifier.type_of("issue"), Type::Issue);
+        assert_eq!(classifier.type_of("Done"), Type::Chore);
+        assert_eq!(classifier.type_of("ISSUE"), Type::Issue);
+        assert!(classifier.types.keys().all(|w| w.to_lowercase() == *w));
+    }
+
+    #[test]
+    fn custom_category() {
+        let classifier = Classifier::builder()
+            .category("ops")
+            .alias("operations", Type::Custom("ops".to_owned()))
+            .build();
+        for text in ["ops: Rotate keys", "operations: Rotate keys"] {
+            assert_eq!(
+                classifier.classify(text),
+                Subject::ConventionalCommit {
+                    breaking_change: false,
+                    category: Type::Custom("ops".to_owned()),
+                    scope: None,
+                    description: "Rotate keys".to_owned(),
+                    text: text.to_owned(),
+                    pull_request: None,
+                }
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    struct Infra;
+
+    impl Rule for Infra {
+        fn name(&self) -> &'static str {
+            "infra"
+        }
+
+        fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+            subject
+                .starts_with("[infra]")
+                .then(|| SubjectRef::new(subject, SubjectKind::Remove))
+        }
+    }
+
+    #[test]
+    fn rules() {
+        let classifier = Classifier::builder()
+            .insert_rule_before(rules::Fixup.name(), Infra)
+            .build();
+        let names: Vec<_> = classifier.rule_names().collect();
+        let index = names.iter().position(|n| *n == "infra").expect("Inserted");
+        assert_eq!(names[index + 1], "fixup");
+        assert_eq!(
+            classifier.classify("[infra] Foo"),
+            Subject::Remove {
+                description: "[infra] Foo".to_owned(),
+                text: "[infra] Foo".to_owned(),
+                pull_request: None,
+            }
+        );
+    }
+
+    #[test]
+    fn remove_rule() {
+        let text = "Merge #123";
+        let classifier = Classifier::builder()
+            .remove_rule(rules::BorsPullRequest.name())
+            .build();
+        assert!(matches!(Subject::from(text), Subject::PullRequest { .. }));
+        assert!(matches!(
+            classifier.classify(text),
+            Subject::ConventionalCommit {
+                category: Type::Other,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn reorder() {
+        let text = "Merged PR 36587: Add Foo calibration to item type";
+        let classifier = Classifier::builder()
+            .remove_rule(rules::ConventionalCommit.name())
+            .insert_rule(0, rules::ConventionalCommit)
+            .build();
+        assert!(matches!(
+            classifier.classify(text),
+            Subject::ConventionalCommit {
+                category: Type::Other,
+                ..
+            }
+        ));
+
+        let text = "Rename Foo";
+        let classifier = Classifier::builder()
+            .rules(vec![Arc::new(rules::Revert), Arc::new(rules::Rename)])
+            .build();
+        assert_eq!(
+            classifier.classify(text),
+            Subject::Rename {
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
+            }
+        );
+        assert_eq!(
+            classifier.classify("feat: Foo"),
+            Subject::Simple {
+                description: "feat: Foo".to_owned(),
+                text: "feat: Foo".to_owned(),
+                pull_request: None,
+            }
+        );
+    }
+
+    #[test]
+    fn prefilter() {
+        let classifier = Classifier::default();
+        for subject in include_str!("../benches/subjects.txt").lines() {
+            let (text, squash) = split_squash(subject);
+            let expected = classifier
+                .rules
+                .iter()
+                .find_map(|rule| rule.classify(text, &classifier))
+                .unwrap_or_else(|| SubjectRef::new(text, SubjectKind::Simple))
+                .with_squash(subject, squash);
+            assert_eq!(classifier.classify_ref(subject), expected, "{}", subject);
+        }
+    }
+
+    #[test]
+    fn classify_all() {
+        let log = "feat: Foo\nMerge #123\nSome text";
+        let subjects: Vec<String> = log.lines().map(ToOwned::to_owned).collect();
+        let expected: Vec<Subject> = log.lines().map(Subject::from).collect();
+        assert_eq!(
+            Classifier::default()
+                .classify_all(&subjects)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            crate::classify_all(log.lines()).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let classifier = Classifier::builder().category("ops").clear().build();
+        assert_eq!(
+            classifier.validate("fix: foo")[0].kind,
+            DiagnosticKind::UnknownType
+        );
+        let classifier = Classifier::builder().category("ops").build();
+        assert!(classifier.validate("ops: rotate keys").is_empty());
+        assert_eq!(
+            classifier.validate("osp: rotate keys")[0]
+                .suggestion
+                .as_deref(),
+            Some("ops")
+        );
+    }
+
+    #[test]
+    fn try_classify() {
+        let classifier = Classifier::builder().alias("infra", Type::Build).build();
+        assert!(classifier.try_classify("infra: Add runners").is_ok());
+        assert_eq!(
+            classifier.try_classify("ops: Rotate keys"),
+            Err(ClassifyError::UnknownType("ops".to_owned()))
+        );
+        assert_eq!(classifier.try_classify(" \t"), Err(ClassifyError::Empty));
+        assert_eq!(
+            classifier.try_classify("[WIP] Foo"),
+            Err(ClassifyError::Unclassified)
+        );
+        let result = classifier.try_classify_ref("fixup! Foo").expect("Fixup");
+        assert_eq!(result.kind(), &SubjectKind::Fixup);
+    }
+
+    #[test]
+    fn explain() {
+        let classifier = Classifier::default();
+        for (text, rule, derivation) in [
+            (
+                "feat(ui): Foo",
+                Some("conventional-commit"),
+                Derivation::Explicit,
+            ),
+            ("fixup! Foo", Some("fixup"), Derivation::Explicit),
+            ("Merge #12", Some("pr-bors"), Derivation::Merge),
+            ("Move Foo to Bar", Some("rename"), Derivation::Keyword),
+            ("Fixed Foo", Some("fix"), Derivation::Keyword),
+            (
+                "Makefile: Foo",
+                Some("conventional-commit"),
+                Derivation::Fallback,
+            ),
+            ("[WIP] Foo", None, Derivation::Fallback),
+        ] {
+            let result = classifier.explain(text);
+            assert_eq!(result.rule, rule, "{}", text);
+            assert_eq!(result.derivation, derivation, "{}", text);
+            assert_eq!(result.subject, classifier.classify_ref(text), "{}", text);
+        }
+        assert!(Derivation::Explicit.confidence() > Derivation::Merge.confidence());
+        assert!(Derivation::Keyword.confidence() > Derivation::Fallback.confidence());
+    }
+
+    #[test]
+    fn alternatives() {
+        let classifier = Classifier::default();
+        let result = classifier.alternatives("Merge #12");
+        let rules: Vec<_> = result.iter().map(|c| c.rule).collect();
+        assert_eq!(rules, [Some("pr-bors"), Some("conventional-commit")]);
+        assert_eq!(result[1].derivation, Derivation::Fallback);
+
+        let result = classifier.alternatives("[WIP] Foo");
+        assert_eq!(result, [classifier.explain("[WIP] Foo")]);
+    }
+}
@@ -0,0 +1,544 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Project configuration read from a `.subject-classifier.toml` file
+//!
+//! ```toml
+//! # Additional conventional commit types
+//! categories = ["ops"]
+//! # Only these types are allowed by the commit message policy
+//! allowed-types = ["feat", "fix", "docs", "ops"]
+//! require-scope = true
+//! # Known scopes, any scope is allowed if empty
+//! scopes = ["api", "ui"]
+//! max-length = 72
+//! # Built-in rules to disable, see `Classifier::rule_names`
+//! disabled-rules = ["pr-azure", "pr-bors"]
+//! # Ticket project keys, see `references::Extractor`, any uppercase key if unset
+//! project-keys = ["JIRA", "CSV-[A-Z]+"]
+//! # Base theme: nerd-font-v2, nerd-font-v3, emoji, ascii or none
+//! icon-theme = "emoji"
+//!
+//! # Additional words for a type or category
+//! [aliases]
+//! bug = "fix"
+//! infra = "ops"
+//!
+//! # Icons by type or subject kind, the other icons are taken from the theme
+//! [icons]
+//! ops = "⚙ "
+//! pull_request = "⇄ "
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::icons::{self, IconTheme, Theme, UnknownTheme};
+use crate::policy::Policy;
+use crate::references::Extractor;
+use crate::rules;
+use crate::{Classifier, Subject, Type};
+
+/// Name of the configuration file
+pub const FILE_NAME: &str = ".subject-classifier.toml";
+
+/// Names returned by [`Subject::kind_name`], usable as [`Config::icons`] keys
+const KIND_NAMES: &[&str] = &[
+    "conventional",
+    "fixup",
+    "merge",
+    "pull_request",
+    "release",
+    "remove",
+    "rename",
+    "revert",
+    "subtree",
+    "simple",
+];
+
+/// Error loading a [`Config`]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read
+    Io(io::Error),
+    /// The file is not valid TOML or contains unknown keys
+    Syntax(toml::de::Error),
+    /// A key has an invalid value
+    InvalidValue {
+        /// Path of the offending key, e.g. `aliases.bug` or `allowed-types[2]`
+        key: String,
+        /// What is wrong with the value
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Syntax(e) => write!(f, "{}", e),
+            Self::InvalidValue { key, message } => {
+                write!(f, "invalid value for {}: {}", key, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Syntax(e) => Some(e),
+            Self::InvalidValue { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl ConfigError {
+    fn invalid(key: String, message: String) -> Self {
+        Self::InvalidValue { key, message }
+    }
+}
+
+/// The file format
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct File {
+    categories: Vec<String>,
+    aliases: BTreeMap<String, String>,
+    allowed_types: Vec<String>,
+    require_scope: bool,
+    scopes: Vec<String>,
+    max_length: Option<usize>,
+    disabled_rules: Vec<String>,
+    project_keys: Option<Vec<String>>,
+    icon_theme: Option<String>,
+    icons: BTreeMap<String, String>,
+}
+
+/// A project configuration
+///
+/// ```rust
+/// use subject_classifier::config::Config;
+/// use subject_classifier::policy::Violation;
+///
+/// let config: Config = "categories = [\"ops\"]\nrequire-scope = true\n".parse()?;
+/// assert_eq!(config.policy.check_with("ops: Foo", &config.classifier), vec![
+///     Violation::MissingScope,
+/// ]);
+/// # Ok::<(), subject_classifier::config::ConfigError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Classifier with the configured types, aliases & rules
+    pub classifier: Classifier,
+    /// The commit message policy
+    pub policy: Policy,
+    /// Icon theme for the subjects without an override
+    pub theme: Theme,
+    /// Icon overrides by type name or [`Subject::kind_name`]
+    pub icons: HashMap<String, String>,
+    /// Extractor for the issue & ticket references of the project
+    pub references: Extractor,
+}
+
+impl Config {
+    /// Find the configuration file in `dir` or its parent directories
+    ///
+    /// The search stops after `ceiling` if `dir` is inside it, e.g. at the repository root.
+    #[must_use]
+    #[inline]
+    pub fn find(dir: &Path, ceiling: Option<&Path>) -> Option<PathBuf> {
+        let depth = ceiling
+            .and_then(|ceiling| dir.ancestors().position(|d| d == ceiling))
+            .map_or(usize::MAX, |i| i.saturating_add(1));
+        dir.ancestors()
+            .take(depth)
+            .map(|d| d.join(FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    /// Load the configuration file found by [`Config::find`], the default configuration if
+    /// there is none
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the found file can not be loaded.
+    #[inline]
+    pub fn discover(dir: &Path, ceiling: Option<&Path>) -> Result<Self, ConfigError> {
+        Self::find(dir, ceiling).map_or_else(|| Ok(Self::default()), |path| Self::load(&path))
+    }
+
+    /// Load a configuration file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can not be read or contains invalid values.
+    #[inline]
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    fn from_file(file: File) -> Result<Self, ConfigError> {
+        let mut builder = Classifier::builder();
+        for name in &file.categories {
+            builder = builder.category(name);
+        }
+        for (word, name) in &file.aliases {
+            let category = Type::from_name(name);
+            if matches!(category, Type::Custom(_)) && !file.categories.contains(name) {
+                return Err(ConfigError::invalid(
+                    format!("aliases.{}", word),
+                    format!("unknown type “{}”, add it to categories", name),
+                ));
+            }
+            builder = builder.alias(word, category);
+        }
+        let rule_names: Vec<_> = rules::defaults().iter().map(|r| r.name()).collect();
+        for (i, name) in file.disabled_rules.iter().enumerate() {
+            if !rule_names.contains(&name.as_str()) {
+                return Err(ConfigError::invalid(
+                    format!("disabled-rules[{}]", i),
+                    format!(
+                        "unknown rule “{}”, expected one of {}",
+                        name,
+                        rule_names.join(", ")
+                    ),
+                ));
+            }
+            builder = builder.remove_rule(name);
+        }
+        let classifier = builder.build();
+
+        let mut allowed_types = vec![];
+        for (i, name) in file.allowed_types.iter().enumerate() {
+            match classifier.type_of(name) {
+                Type::Other => {
+                    return Err(ConfigError::invalid(
+                        format!("allowed-types[{}]", i),
+                        format!("unknown type “{}”", name),
+                    ));
+                }
+                category => allowed_types.push(category),
+            }
+        }
+        let theme = match &file.icon_theme {
+            Some(name) => name.parse().map_err(|e: UnknownTheme| {
+                ConfigError::invalid("icon-theme".to_owned(), e.to_string())
+            })?,
+            None => Theme::default(),
+        };
+        for name in file.icons.keys() {
+            let is_type =
+                !matches!(Type::from_name(name), Type::Custom(_)) || file.categories.contains(name);
+            if !is_type && !KIND_NAMES.contains(&name.as_str()) {
+                return Err(ConfigError::invalid(
+                    format!("icons.{}", name),
+                    "expected a type or subject kind name".to_owned(),
+                ));
+            }
+        }
+
+        let references = match &file.project_keys {
+            Some(keys) => {
+                for (i, key) in keys.iter().enumerate() {
+                    if let Err(e) = Extractor::new([key]) {
+                        return Err(ConfigError::invalid(
+                            format!("project-keys[{}]", i),
+                            e.to_string(),
+                        ));
+                    }
+                }
+                Extractor::new(keys)
+                    .map_err(|e| ConfigError::invalid("project-keys".to_owned(), e.to_string()))?
+            }
+            None => Extractor::default(),
+        };
+
+        Ok(Self {
+            classifier,
+            policy: Policy {
+                require_scope: file.require_scope,
+                scopes: file.scopes,
+                allowed_types,
+                max_length: file.max_length,
+            },
+            theme,
+            icons: file.icons.into_iter().collect(),
+            references,
+        })
+    }
+}
+
+impl IconTheme for Config {
+    /// Overrides for the subject's type take precedence over overrides for its kind
+    #[inline]
+    fn icon<'a>(&'a self, subject: &'a Subject) -> Cow<'a, str> {
+        let icon = subject
+            .category()
+            .and_then(|category| self.icons.get(category.as_str()))
+            .or_else(|| self.icons.get(subject.kind_name()))
+            .map_or_else(|| self.theme.icon(subject), |icon| Cow::Borrowed(icon));
+        match self.columns() {
+            Some(columns) => icons::pad(icon, columns),
+            None => icon,
+        }
+    }
+
+    /// The theme's width, widened to fit all overrides
+    #[inline]
+    fn columns(&self) -> Option<usize> {
+        let overrides = self.icons.values().map(|icon| icons::width(icon)).max();
+        self.theme.columns().max(overrides)
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_file(toml::from_str(s).map_err(ConfigError::Syntax)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::{Config, ConfigError, FILE_NAME};
+    use crate::icons::{width, IconTheme, Theme};
+    use crate::policy::Violation;
+    use crate::{Subject, Type};
+
+    fn invalid(text: &str) -> (String, String) {
+        match text.parse::<Config>() {
+            Err(ConfigError::InvalidValue { key, message }) => (key, message),
+            other => panic!("Expected InvalidValue, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn full() {
+        let config: Config = r#"
+            categories = ["ops"]
+            allowed-types = ["feat", "fix", "bug", "ops"]
+            require-scope = true
+            scopes = ["api", "ui"]
+            max-length = 50
+            disabled-rules = ["pr-azure"]
+            project-keys = ["CSV-FOO"]
+
+            [aliases]
+            bug = "fix"
+            infra = "ops"
+
+            [icons]
+            ops = "O "
+            pull_request = "P "
+            merge = "M "
+        "#
+        .parse()
+        .expect("Valid config");
+
+        assert_eq!(
+            config.classifier.type_of("infra"),
+            Type::Custom("ops".to_owned())
+        );
+        assert_eq!(config.classifier.type_of("bug"), Type::Fix);
+        assert!(!config.classifier.rule_names().any(|n| n == "pr-azure"));
+        assert_eq!(
+            config.policy.allowed_types,
+            vec![
+                Type::Feat,
+                Type::Fix,
+                Type::Fix,
+                Type::Custom("ops".to_owned())
+            ]
+        );
+        assert!(config.policy.require_scope);
+        assert_eq!(config.policy.max_length, Some(50));
+        assert_eq!(
+            config
+                .policy
+                .check_with("docs(db): Foo", &config.classifier),
+            vec![
+                Violation::UnknownScope("db".to_owned()),
+                Violation::DisallowedType("docs".to_owned())
+            ]
+        );
+
+        let subject = config.classifier.classify("infra(api): Foo");
+        assert_eq!(subject.icon_with(&config), "O ");
+        let subject = config
+            .classifier
+            .classify("Merge pull request #1 from foo/bar");
+        assert_eq!(subject.icon_with(&config), "P ");
+        let subject = Subject::from("Merge branch 'develop'");
+        assert_eq!(subject.icon_with(&config), "M ");
+        let subject = Subject::from("docs: Foo");
+        assert_eq!(subject.icon_with(&config), subject.icon());
+
+        let subject = Subject::from("security: Fix CSV-FOO-1234 & UTF-8 names");
+        let references = subject.references_with(&config.references);
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].id, "CSV-FOO-1234");
+    }
+
+    #[test]
+    fn icon_theme() {
+        let config: Config = "icon-theme = \"ascii\"\n[icons]\nfix = \"F \""
+            .parse()
+            .expect("Valid config");
+        assert_eq!(config.theme, Theme::Ascii);
+        assert_eq!(config.columns(), Some(14));
+        assert_eq!(
+            Subject::from("fix: Foo").icon_with(&config),
+            format!("{:14}", "F")
+        );
+        assert_eq!(
+            Subject::from("docs: Foo").icon_with(&config),
+            format!("{:14}", "[docs]")
+        );
+
+        let config: Config = "[icons]\nfix = \"[FIX] \"".parse().expect("Valid config");
+        assert_eq!(config.columns(), Some(6));
+        assert_eq!(Subject::from("fix: Foo").icon_with(&config), "[FIX] ");
+        assert_eq!(width(&Subject::from("docs: Foo").icon_with(&config)), 6);
+    }
+
+    #[test]
+    fn module_example() {
+        let example: String = include_str!("config.rs")
+            .lines()
+            .filter_map(|line| line.strip_prefix("//!"))
+            .skip_while(|line| *line != " ```toml")
+            .skip(1)
+            .take_while(|line| *line != " ```")
+            .map(|line| format!("{}\n", line.trim_start()))
+            .collect();
+        assert!(example.contains("[icons]"), "{}", example);
+        let config: Config = example.parse().expect("Valid example");
+        assert_eq!(config.theme, Theme::Emoji);
+        assert_eq!(config.icons.len(), 2);
+    }
+
+    #[test]
+    fn empty() {
+        let config: Config = "".parse().expect("Valid config");
+        assert_eq!(config.policy, crate::policy::Policy::default());
+        assert!(config.icons.is_empty());
+        let subject = Subject::from("Fix UTF-8 in CSV-FOO-1234");
+        let references = subject.references_with(&config.references);
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].id, "CSV-FOO-1234");
+        assert_eq!(
+            config.classifier.rule_names().count(),
+            crate::rules::defaults().len()
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            invalid("[aliases]\nbug = \"fxi\""),
+            (
+                "aliases.bug".to_owned(),
+                "unknown type “fxi”, add it to categories".to_owned()
+            )
+        );
+        assert_eq!(
+            invalid("allowed-types = [\"feat\", \"ops\"]").0,
+            "allowed-types[1]"
+        );
+        assert_eq!(
+            invalid("disabled-rules = [\"gitlab\"]").0,
+            "disabled-rules[0]"
+        );
+        assert_eq!(invalid("[icons]\nfoo = \"x\"").0, "icons.foo");
+        assert_eq!(invalid("icon-theme = \"nerdfont\"").0, "icon-theme");
+        assert_eq!(
+            invalid("project-keys = [\"JIRA\", \"[A-Z\"]").0,
+            "project-keys[1]"
+        );
+
+        let error = "require-scope = true\nscope = [\"ui\"]\n"
+            .parse::<Config>()
+            .expect_err("Unknown key");
+        assert!(matches!(error, ConfigError::Syntax(_)));
+        let message = error.to_string();
+        assert!(message.contains("line 2"), "{}", message);
+        assert!(message.contains("unknown field `scope`"), "{}", message);
+
+        let error = "max-length = \"72\""
+            .parse::<Config>()
+            .expect_err("Wrong type");
+        assert!(error.to_string().contains("max-length"), "{}", error);
+    }
+
+    #[test]
+    fn discovery() {
+        let dir = TempDir::new().expect("Temporary directory");
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).expect("Created directories");
+        assert_eq!(Config::find(&nested, Some(dir.path())), None);
+        assert!(Config::discover(&nested, Some(dir.path())).is_ok());
+
+        let path = dir.path().join(FILE_NAME);
+        fs::write(&path, "require-scope = true\n").expect("Written config");
+        assert_eq!(Config::find(&nested, Some(dir.path())), Some(path.clone()));
+        assert!(
+            Config::discover(&nested, Some(dir.path()))
+                .expect("Valid config")
+                .policy
+                .require_scope
+        );
+
+        fs::write(&path, "require-scope = 1\n").expect("Written config");
+        assert!(matches!(
+            Config::discover(&nested, Some(dir.path())),
+            Err(ConfigError::Syntax(_))
+        ));
+        let outside = nested.join(FILE_NAME);
+        fs::write(&outside, "max-length = 50\n").expect("Written config");
+        assert_eq!(Config::find(&nested, Some(&nested)), Some(outside.clone()));
+        fs::remove_file(&outside).expect("Removed config");
+        assert_eq!(Config::find(&nested, Some(&dir.path().join("a"))), None);
+
+        assert!(matches!(
+            Config::load(&dir.path().join("missing.toml")),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Walk the history of a local git repository using [git2](https://docs.rs/git2)
+//!
+//! ```rust,no_run
+//! use subject_classifier::changelog::Changelog;
+//!
+//! let repo = git2::Repository::open(".")?;
+//! let commits = subject_classifier::history::walk(&repo, "v0.4.0..HEAD")?
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let changelog = Changelog::new(commits.iter().map(|c| (c.subject.clone(), c.metadata())));
+//! # Ok::<(), git2::Error>(())
+//! ```
+
+use git2::{Oid, Repository, Revwalk, Sort, Time};
+
+use crate::changelog::Metadata;
+use crate::classifier::DEFAULT;
+use crate::{Classifier, Subject};
+
+/// A classified commit
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Commit {
+    /// Commit id
+    pub id: Oid,
+    /// Author name
+    pub author: String,
+    /// Author date
+    pub time: Time,
+    /// The classified commit message
+    pub subject: Subject,
+}
+
+impl Commit {
+    /// The author date as `YYYY-MM-DD` in the author's time zone
+    #[must_use]
+    #[inline]
+    pub fn date(&self) -> String {
+        let offset = i64::from(self.time.offset_minutes()).saturating_mul(60);
+        let days = self
+            .time
+            .seconds()
+            .saturating_add(offset)
+            .div_euclid(86_400);
+        let (year, month, day) = civil_from_days(days);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// The commit information used by [`Changelog::new`](crate::changelog::Changelog::new)
+    #[must_use]
+    #[inline]
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            id: Some(self.id.to_string()),
+            author: Some(self.author.clone()),
+            date: Some(self.date()),
+        }
+    }
+}
+
+/// Iterator over the classified commits of a revision range, newest first
+pub struct History<'r> {
+    repo: &'r Repository,
+    walk: Revwalk<'r>,
+    classifier: &'r Classifier,
+}
+
+impl Iterator for History<'_> {
+    type Item = Result<Commit, git2::Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = match self.walk.next()? {
+            Ok(id) => id,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(self.repo.find_commit(id).map(|commit| {
+            let message = String::from_utf8_lossy(commit.message_bytes());
+            let author = commit.author();
+            Commit {
+                id,
+                author: String::from_utf8_lossy(author.name_bytes()).into_owned(),
+                time: author.when(),
+                subject: self.classifier.classify_message(&message).subject,
+            }
+        }))
+    }
+}
+
+impl Classifier {
+    /// Classify the commits of a revision range, newest first
+    ///
+    /// `range` is anything `git rev-parse` understands, e.g. `HEAD`, `v1.0.0..main` or
+    /// `main...feature`. The whole commit message is classified, so `BREAKING CHANGE` footers
+    /// are recognized.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `range` can not be resolved.
+    #[inline]
+    pub fn walk<'r>(
+        &'r self,
+        repo: &'r Repository,
+        range: &str,
+    ) -> Result<History<'r>, git2::Error> {
+        let spec = repo.revparse(range)?;
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        match (spec.from(), spec.to()) {
+            (Some(from), Some(to)) => {
+                walk.push(to.id())?;
+                if spec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+                    walk.push(from.id())?;
+                    walk.hide(repo.merge_base(from.id(), to.id())?)?;
+                } else {
+                    walk.hide(from.id())?;
+                }
+            }
+            (Some(rev), None) | (None, Some(rev)) => walk.push(rev.id())?,
+            (None, None) => walk.push_head()?,
+        }
+        Ok(History {
+            repo,
+            walk,
+            classifier: self,
+        })
+    }
+}
+
+/// Classify the commits of a revision range with the default [`Classifier`], see
+/// [`Classifier::walk`]
+///
+/// # Errors
+///
+/// Returns an error if `range` can not be resolved.
+#[inline]
+pub fn walk<'r>(repo: &'r Repository, range: &str) -> Result<History<'r>, git2::Error> {
+    DEFAULT.walk(repo, range)
+}
+
+/// Converts days since 1970-01-01 to a `(year, month, day)` date
+#[allow(clippy::arithmetic_side_effects, clippy::integer_division)]
+// arithmetic: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::{Oid, Repository, Signature, Time};
+    use tempfile::TempDir;
+
+    use super::civil_from_days;
+    use crate::changelog::Changelog;
+    use crate::{history, Subject, Type};
+
+    struct Fixture {
+        _dir: TempDir,
+        repo: Repository,
+        time: i64,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let dir = TempDir::new().expect("Temporary directory");
+            let repo = Repository::init(dir.path()).expect("Initialized repository");
+            Self {
+                _dir: dir,
+                repo,
+                time: 1_665_273_600, // 2022-10-09 00:00 UTC
+            }
+        }
+
+        fn commit(&mut self, message: &str) -> Oid {
+            self.time += 3600;
+            let signature =
+                Signature::new("Jane Doe", "jane@example.com", &Time::new(self.time, 120))
+                    .expect("Valid signature");
+            let tree_id = self
+                .repo
+                .treebuilder(None)
+                .and_then(|b| b.write())
+                .expect("Written tree");
+            let tree = self.repo.find_tree(tree_id).expect("Tree");
+            let parent = self.repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<_> = parent.iter().collect();
+            self.repo
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    message,
+                    &tree,
+                    &parents,
+                )
+                .expect("Created commit")
+        }
+
+        fn subjects(&self, range: &str) -> Vec<Subject> {
+            history::walk(&self.repo, range)
+                .expect("Valid range")
+                .map(|c| c.expect("Readable commit").subject)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let mut fixture = Fixture::new();
+        let first = fixture.commit("Initial commit");
+        fixture.commit("Release v0.1.0");
+        let last = fixture.commit("feat(ui): Foo\n\nBREAKING CHANGE: Bar\n");
+
+        let commits: Vec<_> = history::walk(&fixture.repo, "HEAD")
+            .expect("Valid range")
+            .collect::<Result<_, _>>()
+            .expect("Readable commits");
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[0].id, last);
+        assert_eq!(commits[0].author, "Jane Doe");
+        assert_eq!(commits[0].date(), "2022-10-09");
+        assert_eq!(commits[2].id, first);
+        assert!(commits[0].subject.breaking_change());
+        assert_eq!(commits[0].subject.category(), Some(&Type::Feat));
+        assert!(matches!(commits[1].subject, Subject::Release { .. }));
+        assert_eq!(commits[2].subject.description(), "Initial commit");
+    }
+
+    #[test]
+    fn range() {
+        let mut fixture = Fixture::new();
+        let first = fixture.commit("Initial commit");
+        fixture.commit("fix: Foo");
+        fixture.commit("docs: Bar");
+        assert_eq!(fixture.subjects("HEAD").len(), 3);
+        assert_eq!(fixture.subjects(&format!("{}..HEAD", first)).len(), 2);
+        assert_eq!(fixture.subjects("HEAD~1..HEAD").len(), 1);
+        assert!(history::walk(&fixture.repo, "v9.9.9..HEAD").is_err());
+    }
+
+    #[test]
+    fn changelog() {
+        let mut fixture = Fixture::new();
+        fixture.commit("feat: Foo");
+        fixture.commit("Release v0.1.0");
+        fixture.commit("Deprecate Bar");
+        let commits: Vec<_> = history::walk(&fixture.repo, "HEAD")
+            .expect("Valid range")
+            .collect::<Result<_, _>>()
+            .expect("Readable commits");
+        let changelog = Changelog::new(commits.iter().map(|c| (c.subject.clone(), c.metadata())));
+        assert_eq!(changelog.unreleased.changes.len(), 1);
+        assert_eq!(changelog.releases[0].date.as_deref(), Some("2022-10-09"));
+        assert_eq!(
+            changelog.unreleased.changes[0].metadata.author.as_deref(),
+            Some("Jane Doe")
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_274), (2022, 10, 9));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+}
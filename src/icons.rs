@@ -0,0 +1,538 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Icon themes for [`Subject::icon_with`]
+//!
+//! [`Subject::icon`] uses private-use [Nerd Font](https://www.nerdfonts.com) v2 codepoints which
+//! render as boxes without a patched font, choose another [`Theme`] in that case.
+//!
+//! ```rust
+//! use subject_classifier::icons::Theme;
+//! use subject_classifier::Subject;
+//!
+//! let subject = Subject::from("feat(ui): Dark mode");
+//! assert_eq!(subject.icon_with(&Theme::Emoji), "✨");
+//! assert_eq!(subject.icon_with(&Theme::Ascii), "[feat]        ");
+//! assert_eq!(subject.icon_with(&Theme::NerdFontV2), subject.icon());
+//! ```
+//!
+//! All icons of a built-in theme have the same [display width](width), so descriptions
+//! printed after them line up in a terminal.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{Subject, SubtreeOperation, Type};
+
+/// Maps subjects to icons
+///
+/// Implement it for custom icon sets:
+///
+/// ```rust
+/// use std::borrow::Cow;
+/// use subject_classifier::icons::{IconTheme, Theme};
+/// use subject_classifier::Subject;
+///
+/// struct Releases;
+///
+/// impl IconTheme for Releases {
+///     fn icon<'a>(&'a self, subject: &'a Subject) -> Cow<'a, str> {
+///         match subject {
+///             Subject::Release { .. } => Cow::Borrowed("🚀"),
+///             _ => Theme::Emoji.icon(subject),
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Subject::from("Release v1.0.0").icon_with(&Releases), "🚀");
+/// ```
+pub trait IconTheme {
+    /// The icon representing `subject`
+    fn icon<'a>(&'a self, subject: &'a Subject) -> Cow<'a, str>;
+
+    /// The display width all icons are padded to, `None` if icons are not aligned
+    #[inline]
+    fn columns(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Number of terminal cells needed to display `icon`
+///
+/// Most Nerd Font glyphs are in the private use area and count as one cell, emoji as two. The
+/// Nerd Font v2 Material Design glyphs overlap the CJK compatibility ideographs, so they count
+/// as two cells, like [`Type::Perf`]'s `\u{f9c4}`.
+///
+/// ```rust
+/// use subject_classifier::icons::width;
+///
+/// assert_eq!(width("\u{f188} "), 2);
+/// assert_eq!(width("🐛"), 2);
+/// assert_eq!(width("\u{f9c4}"), 2);
+/// assert_eq!(width("[fix] "), 6);
+/// ```
+#[must_use]
+#[inline]
+pub fn width(icon: &str) -> usize {
+    icon.width()
+}
+
+/// Append spaces to `icon` until it is `columns` cells wide
+///
+/// Icons which are already wide enough are returned unchanged.
+#[must_use]
+#[inline]
+pub fn pad(icon: Cow<'_, str>, columns: usize) -> Cow<'_, str> {
+    let missing = columns.saturating_sub(width(&icon));
+    if missing == 0 {
+        return icon;
+    }
+    let mut result = icon.into_owned();
+    result.extend(std::iter::repeat_n(' ', missing));
+    Cow::Owned(result)
+}
+
+/// The built-in icon themes
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Theme {
+    /// Nerd Font v2 glyphs, used by [`Subject::icon`]
+    #[default]
+    NerdFontV2,
+    /// Nerd Font v3 glyphs, Material Design icons moved to the `U+F0001` plane
+    NerdFontV3,
+    /// Plain emoji, no patched font required
+    Emoji,
+    /// ASCII tags like `[fix]`
+    Ascii,
+    /// No icons at all
+    None,
+}
+
+impl Theme {
+    /// All built-in themes
+    pub const ALL: [Self; 5] = [
+        Self::NerdFontV2,
+        Self::NerdFontV3,
+        Self::Emoji,
+        Self::Ascii,
+        Self::None,
+    ];
+
+    /// The theme name used by [`Theme::from_str`]
+    #[must_use]
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::NerdFontV2 => "nerd-font-v2",
+            Self::NerdFontV3 => "nerd-font-v3",
+            Self::Emoji => "emoji",
+            Self::Ascii => "ascii",
+            Self::None => "none",
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error parsing a [`Theme`] name
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownTheme(pub String);
+
+impl fmt::Display for UnknownTheme {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = Theme::ALL.iter().map(|t| t.name()).collect();
+        write!(
+            f,
+            "unknown icon theme “{}”, expected one of {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownTheme {}
+
+impl FromStr for Theme {
+    type Err = UnknownTheme;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| UnknownTheme(s.to_owned()))
+    }
+}
+
+impl IconTheme for Theme {
+    #[inline]
+    fn icon<'a>(&'a self, subject: &'a Subject) -> Cow<'a, str> {
+        let icon = match self {
+            Self::NerdFontV2 => Cow::Borrowed(subject.icon()),
+            Self::NerdFontV3 => Cow::Borrowed(nerd_font_v3(subject)),
+            Self::Emoji => Cow::Borrowed(emoji(subject)),
+            Self::Ascii => ascii(subject),
+            Self::None => Cow::Borrowed(""),
+        };
+        match self.columns() {
+            Some(columns) => pad(icon, columns),
+            None => icon,
+        }
+    }
+
+    #[inline]
+    fn columns(&self) -> Option<usize> {
+        Some(match self {
+            Self::NerdFontV2 | Self::NerdFontV3 | Self::Emoji => 2,
+            // `[improvement] `
+            Self::Ascii => ASCII_TAG_LEN + 3,
+            Self::None => 0,
+        })
+    }
+}
+
+/// Longest tag of the ASCII theme, longer custom category names are truncated
+const ASCII_TAG_LEN: usize = 11;
+
+/// Nerd Font v3 removed the `nf-mdi-*` glyphs, the `nf-md-*` replacements use the codepoints
+/// of the Material Design Icons project
+const fn nerd_font_v3(subject: &Subject) -> &str {
+    match subject {
+        Subject::ConventionalCommit {
+            breaking_change: false,
+            category,
+            ..
+        } => match category {
+            Type::Archive => "\u{f003c} ",
+            Type::I18n => "\u{f05ca} ",
+            Type::Perf => "\u{f04c5} ",
+            _ => subject.icon(),
+        },
+        _ => subject.icon(),
+    }
+}
+
+const fn emoji(subject: &Subject) -> &'static str {
+    match subject {
+        Subject::Fixup { .. } => "🩹",
+        Subject::ConventionalCommit {
+            breaking_change: true,
+            ..
+        } => "💥",
+        Subject::ConventionalCommit { category, .. } => match category {
+            Type::Archive => "📦",
+            Type::Build => "🔨",
+            Type::Change | Type::Improvement => "🔄",
+            Type::Chore => "🚧",
+            Type::Ci => "👷",
+            Type::Deprecate => "⛔",
+            Type::Dev => "🚩",
+            Type::Deps => "📌",
+            Type::Docs => "📝",
+            Type::Feat => "✨",
+            Type::Issue => "🎫",
+            Type::Fix => "🐛",
+            Type::I18n => "🌐",
+            Type::Other | Type::Custom(_) => "  ",
+            Type::Perf => "⚡",
+            Type::Refactor => "🧹",
+            Type::Repo => "📚",
+            Type::Security => "🔒",
+            Type::Style => "🎨",
+            Type::Test => "✅",
+        },
+        Subject::SubtreeCommit { operation, .. } => match operation {
+            SubtreeOperation::Import { .. } => "📥",
+            SubtreeOperation::Split { .. } => "🪓",
+            SubtreeOperation::Update { .. } => "🔃",
+        },
+        Subject::Simple { .. } => "  ",
+        Subject::Release { .. } => "🔖",
+        Subject::Remove { .. } => "🔥",
+        Subject::Rename { .. } => "🚚",
+        Subject::Revert { .. } => "⏪",
+        Subject::PullRequest { .. } => "🔀",
+        Subject::Merge { .. } => "🪢",
+    }
+}
+
+fn ascii(subject: &Subject) -> Cow<'_, str> {
+    let tag = match subject {
+        Subject::ConventionalCommit {
+            breaking_change: true,
+            ..
+        } => "breaking",
+        Subject::ConventionalCommit {
+            category: Type::Other,
+            ..
+        }
+        | Subject::Simple { .. } => return Cow::Borrowed(""),
+        Subject::ConventionalCommit { category, .. } => {
+            let name = category.as_str();
+            name.char_indices()
+                .nth(ASCII_TAG_LEN)
+                .map_or(name, |(end, _)| &name[..end])
+        }
+        Subject::PullRequest { .. } => "pr",
+        Subject::SubtreeCommit { .. } => "subtree",
+        _ => subject.kind_name(),
+    };
+    Cow::Owned(format!("[{}] ", tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::{pad, width, IconTheme, Theme, UnknownTheme};
+    use crate::{Forge, Subject, SubtreeOperation, Type};
+
+    /// Every `Type` & `Subject` variant
+    fn variants() -> Vec<Subject> {
+        let types = [
+            Type::Archive,
+            Type::Build,
+            Type::Change,
+            Type::Chore,
+            Type::Ci,
+            Type::Dev,
+            Type::Deps,
+            Type::Docs,
+            Type::Deprecate,
+            Type::Feat,
+            Type::Fix,
+            Type::I18n,
+            Type::Issue,
+            Type::Improvement,
+            Type::Other,
+            Type::Perf,
+            Type::Refactor,
+            Type::Repo,
+            Type::Security,
+            Type::Style,
+            Type::Test,
+            Type::Custom("ops".to_owned()),
+            Type::Custom("infrastructure-as-code".to_owned()),
+        ];
+        let mut result: Vec<Subject> = types
+            .into_iter()
+            .flat_map(|category| {
+                [false, true].map(|breaking_change| Subject::ConventionalCommit {
+                    breaking_change,
+                    category: category.clone(),
+                    scope: None,
+                    description: "Foo".to_owned(),
+                    text: "Foo".to_owned(),
+                    pull_request: None,
+                })
+            })
+            .collect();
+        let (subtree, git_ref) = ("foo".to_owned(), "v1".to_owned());
+        for operation in [
+            SubtreeOperation::Import {
+                subtree: subtree.clone(),
+                git_ref: git_ref.clone(),
+            },
+            SubtreeOperation::Split {
+                subtree: subtree.clone(),
+                git_ref: git_ref.clone(),
+            },
+            SubtreeOperation::Update { subtree, git_ref },
+        ] {
+            result.push(Subject::SubtreeCommit {
+                operation,
+                description: "Foo".to_owned(),
+                text: "Foo".to_owned(),
+                pull_request: None,
+            });
+        }
+        result.extend([
+            Subject::Fixup {
+                description: "fixup! Foo".to_owned(),
+                text: "fixup! Foo".to_owned(),
+                pull_request: None,
+            },
+            Subject::Merge {
+                branches: vec!["foo".to_owned()],
+                remote: None,
+                target: None,
+                description: "Merge branch 'foo'".to_owned(),
+                text: "Merge branch 'foo'".to_owned(),
+                pull_request: None,
+            },
+            Subject::PullRequest {
+                id: Some("1".to_owned()),
+                description: "Foo".to_owned(),
+                text: "Foo (#1)".to_owned(),
+                forge: Forge::GitHub,
+                source: None,
+                target: None,
+                remote: None,
+                repository: None,
+            },
+            Subject::Release {
+                version: "1.0.0".to_owned(),
+                scope: None,
+                description: "Release 1.0.0".to_owned(),
+                text: "Release 1.0.0".to_owned(),
+                pull_request: None,
+            },
+            Subject::Remove {
+                description: "Remove Foo".to_owned(),
+                text: "Remove Foo".to_owned(),
+                pull_request: None,
+            },
+            Subject::Rename {
+                description: "Rename Foo".to_owned(),
+                text: "Rename Foo".to_owned(),
+                pull_request: None,
+            },
+            Subject::Revert {
+                description: "Revert Foo".to_owned(),
+                text: "Revert Foo".to_owned(),
+                pull_request: None,
+            },
+            Subject::Simple {
+                description: "Foo".to_owned(),
+                text: "Foo".to_owned(),
+                pull_request: None,
+            },
+        ]);
+        result
+    }
+
+    fn subjects() -> Vec<Subject> {
+        include_str!("../benches/subjects.txt")
+            .lines()
+            .map(Subject::from)
+            .collect()
+    }
+
+    #[test]
+    fn aligned() {
+        for theme in Theme::ALL {
+            let columns = theme.columns().expect("Aligned theme");
+            for subject in variants() {
+                let icon = subject.icon_with(&theme);
+                assert_eq!(width(&icon), columns, "{} {:?} {:?}", theme, subject, icon);
+            }
+        }
+        for subject in variants() {
+            assert_eq!(width(subject.icon()), 2, "{:?}", subject);
+        }
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(pad(Cow::Borrowed("[fix] "), 8), "[fix]   ");
+        assert_eq!(pad(Cow::Borrowed("🐛"), 2), "🐛");
+        assert_eq!(pad(Cow::Borrowed("[improvement] "), 2), "[improvement] ");
+        assert!(matches!(pad(Cow::Borrowed("x"), 1), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn names() {
+        for theme in Theme::ALL {
+            assert_eq!(theme.name().parse(), Ok(theme));
+        }
+        assert_eq!(
+            "nerdfont".parse::<Theme>(),
+            Err(UnknownTheme("nerdfont".to_owned()))
+        );
+        assert_eq!(Theme::default(), Theme::NerdFontV2);
+    }
+
+    #[test]
+    fn nerd_font_v2_is_default() {
+        for subject in subjects() {
+            assert_eq!(subject.icon_with(&Theme::NerdFontV2), subject.icon());
+        }
+    }
+
+    #[test]
+    fn nerd_font_v3() {
+        let subject = Subject::from("perf: Foo");
+        assert_eq!(subject.icon(), "\u{f9c4}");
+        assert_eq!(subject.icon_with(&Theme::NerdFontV3), "\u{f04c5} ");
+        let subject = Subject::from("fix: Foo");
+        assert_eq!(subject.icon_with(&Theme::NerdFontV3), subject.icon());
+        // No glyph of the removed Material Design range is left
+        for subject in subjects() {
+            let icon = subject.icon_with(&Theme::NerdFontV3);
+            assert!(
+                !icon.chars().any(|c| ('\u{f500}'..='\u{fd46}').contains(&c)),
+                "{:?}",
+                subject
+            );
+        }
+    }
+
+    #[test]
+    fn emoji() {
+        for (text, icon) in [
+            ("feat!: Foo", "💥"),
+            ("docs: Foo", "📝"),
+            ("Merge pull request #1 from foo/bar", "🔀"),
+            ("Release v1.0.0", "🔖"),
+            ("fixup! Foo", "🩹"),
+        ] {
+            assert_eq!(
+                Subject::from(text).icon_with(&Theme::Emoji),
+                icon,
+                "{}",
+                text
+            );
+        }
+        // No private-use codepoints
+        for subject in subjects() {
+            let icon = subject.icon_with(&Theme::Emoji);
+            assert!(!icon.chars().any(|c| ('\u{e000}'..='\u{f8ff}').contains(&c)));
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        for (text, icon) in [
+            ("fix(ui): Foo", "[fix] "),
+            ("feat!: Foo", "[breaking] "),
+            ("Merge pull request #1 from foo/bar", "[pr] "),
+            ("Release v1.0.0", "[release] "),
+            ("Remove Foo", "[remove] "),
+            ("Makefile: Foo", ""),
+        ] {
+            assert_eq!(
+                Subject::from(text).icon_with(&Theme::Ascii),
+                format!("{:14}", icon),
+                "{}",
+                text
+            );
+        }
+        for subject in subjects() {
+            assert!(subject.icon_with(&Theme::Ascii).is_ascii());
+            assert_eq!(Theme::None.icon(&subject), "");
+        }
+    }
+}
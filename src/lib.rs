@@ -27,59 +27,117 @@
 //!         subject.scope(),
 //!         subject.description());
 //! ```
-use regex::{Captures, Regex, RegexBuilder};
-
-use once_cell::sync::Lazy;
 macro_rules! regex {
     ($name:ident, $re:expr $(,)?) => {
         static $name: Lazy<Regex> = Lazy::new(|| Regex::new($re).expect("Valid Regex"));
     };
 }
 
-regex!(
-    CONVENTIONAL_COMMIT_REGEX,
-    r"(?i)^(SECURITY FIX!?|BREAKING CHANGE!?|\w+!?)(\(.+\)!?)?[/:\s]*(.+)"
-);
-
-regex!(ADD_REGEX, r"(?i)^add:?\s*");
-regex!(FIX_REGEX, r"(?i)^(bug)?fix(ing|ed)?(\(.+\))?[/:\s]+");
-
-regex!(UPDATE_REGEX, r#"^Update :?(.+) to (.+)"#);
-regex!(SPLIT_REGEX, r#"^Split '(.+)/' into commit '(.+)'"#);
-regex!(IMPORT_REGEX, r#"^:?(.+) Import .+⸪(.+)"#);
-
-regex!(
-    PR_REGEX,
-    r"^Merge (?:remote-tracking branch '.+/pr/(\d+)'|pull request #(\d+) from .+)$"
-);
-// https://github.com/apps/bors
-regex!(PR_REGEX_BORS, r"^Merge #(\d+)");
-regex!(PR_REGEX_BB, r"^Merge pull request #(\d+) in .+ from .+$");
-regex!(PR_REGEX_AZURE, r"^Merged PR (\d+): (.*)$");
-
-static RELEASE_REGEX1: Lazy<Regex> = Lazy::new(|| {
-    RegexBuilder::new(r#"^(?:Release|Bump) :?(.+)@v?([0-9.]+)\b.*"#)
-        .case_insensitive(true)
-        .build()
-        .expect("Valid Regex")
-});
-
-static RELEASE_REGEX2: Lazy<Regex> = Lazy::new(|| {
-    RegexBuilder::new(r#"^(?:Release|Bump)\s.*?v?([0-9.]+).*"#)
-        .case_insensitive(true)
-        .build()
-        .expect("Valid Regex")
-});
+pub mod changelog;
+mod classifier;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "git2")]
+pub mod history;
+pub mod icons;
+mod message;
+#[cfg(feature = "rayon")]
+mod parallel;
+pub mod policy;
+pub mod references;
+pub mod rules;
+#[cfg(feature = "serde")]
+mod serialize;
+mod subject_ref;
+mod validate;
+pub mod version;
+pub use classifier::{Classification, Classifier, ClassifierBuilder, ClassifyError};
+pub use message::{CommitMessage, Footer};
+#[cfg(feature = "rayon")]
+pub use parallel::par_classify_all;
+pub use subject_ref::{Span, SubjectKind, SubjectRef, SubtreeOperationKind};
+pub use validate::{validate, validate_message, Diagnostic, DiagnosticKind};
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use icons::IconTheme;
+use references::{Extractor, Reference};
+
+/// Classify many subjects with the default [`Classifier`], see [`Classifier::classify_all`]
+///
+/// ```rust
+/// let log = "feat: Foo\nRelease v1.0.0";
+/// let subjects: Vec<_> = subject_classifier::classify_all(log.lines()).collect();
+/// assert_eq!(subjects.len(), 2);
+/// ```
+#[inline]
+pub fn classify_all<'a, I>(subjects: I) -> impl Iterator<Item = Subject> + 'a
+where
+    I: IntoIterator + 'a,
+    I::Item: AsRef<str>,
+{
+    classifier::DEFAULT.classify_all(subjects)
+}
 
 /// Represents different subtree operations encoded in the commit message.
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "operation", rename_all = "snake_case")
+)]
 pub enum SubtreeOperation {
     Import { subtree: String, git_ref: String },
     Split { subtree: String, git_ref: String },
     Update { subtree: String, git_ref: String },
 }
 
+/// The forge or merge bot which merged a pull request
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Forge {
+    Azure,
+    Bitbucket,
+    Bors,
+    Gerrit,
+    GitHub,
+    GitLab,
+    /// Recognized by a custom [`Rule`](rules::Rule)
+    Other,
+}
+
+impl Forge {
+    /// Human readable name
+    #[must_use]
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Azure => "Azure DevOps",
+            Self::Bitbucket => "Bitbucket",
+            Self::Bors => "Bors",
+            Self::Gerrit => "Gerrit",
+            Self::GitHub => "GitHub",
+            Self::GitLab => "GitLab",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for Forge {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// The type of the commit
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -105,15 +163,131 @@ pub enum Type {
     Security,
     Style,
     Test,
+    /// A category registered via [`ClassifierBuilder::category`]
+    Custom(String),
+}
+
+/// Words recognized as a conventional commit type by the default [`Classifier`]
+const TYPES: &[(&str, Type)] = &[
+    ("archive", Type::Archive),
+    ("build", Type::Build),
+    ("breaking change", Type::Change),
+    ("change", Type::Change),
+    ("chore", Type::Chore),
+    ("ci", Type::Ci),
+    ("deprecate", Type::Deprecate),
+    ("deps", Type::Deps),
+    ("dev", Type::Dev),
+    ("docs", Type::Docs),
+    ("add", Type::Feat),
+    ("feat", Type::Feat),
+    ("feature", Type::Feat),
+    ("bugfix", Type::Fix),
+    ("fix", Type::Fix),
+    ("hotfix", Type::Fix),
+    ("security", Type::Security),
+    ("security fix", Type::Security),
+    ("i18n", Type::I18n),
+    ("gi", Type::Issue),
+    ("issue", Type::Issue),
+    ("done", Type::Issue),
+    ("improvement", Type::Improvement),
+    ("perf", Type::Perf),
+    ("internal", Type::Refactor),
+    ("refactor", Type::Refactor),
+    ("repo", Type::Repo),
+    ("style", Type::Style),
+    ("test", Type::Test),
+    ("tests", Type::Test),
+];
+
+/// Canonical names of the built-in types
+const TYPE_NAMES: &[(&str, Type)] = &[
+    ("archive", Type::Archive),
+    ("build", Type::Build),
+    ("change", Type::Change),
+    ("chore", Type::Chore),
+    ("ci", Type::Ci),
+    ("dev", Type::Dev),
+    ("deps", Type::Deps),
+    ("docs", Type::Docs),
+    ("deprecate", Type::Deprecate),
+    ("feat", Type::Feat),
+    ("fix", Type::Fix),
+    ("i18n", Type::I18n),
+    ("issue", Type::Issue),
+    ("improvement", Type::Improvement),
+    ("other", Type::Other),
+    ("perf", Type::Perf),
+    ("refactor", Type::Refactor),
+    ("repo", Type::Repo),
+    ("security", Type::Security),
+    ("style", Type::Style),
+    ("test", Type::Test),
+];
+
+impl Type {
+    /// The canonical lowercase name of the type, e.g. `feat` for `Type::Feat`
+    ///
+    /// The name of a `Type::Custom` is the registered name.
+    #[must_use]
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Custom(name) => name,
+            _ => TYPE_NAMES
+                .iter()
+                .find(|(_, t)| t == self)
+                .map_or("other", |(name, _)| name),
+        }
+    }
+
+    /// Inverse of [`Type::as_str`], unknown names are a `Type::Custom`
+    ///
+    /// Unlike [`Classifier::type_of`] no aliases are recognized.
+    #[must_use]
+    #[inline]
+    pub fn from_name(name: &str) -> Self {
+        TYPE_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map_or_else(|| Self::Custom(name.to_owned()), |(_, t)| t.clone())
+    }
 }
+
 /// Classified subject
 ///
+/// Every variant stores the untouched subject line as `text` and the subject without type,
+/// scope, squash merge reference or other markup as `description`. The id of the squash merge
+/// reference GitHub appends to a subject, e.g. `feat: Foo (#123)`, is stored as
+/// `pull_request`, see [`Subject::pull_request`].
+///
 /// ```rust
 /// use subject_classifier::Subject;
 ///
-/// let subject = Subject::from("feat: Some new feature");
+/// let subject = Subject::from("feat!: Some new feature");
+/// assert_eq!(subject.description(), "Some new feature");
+/// assert_eq!(subject.text(), "feat!: Some new feature");
+/// assert_eq!(subject.decorated().to_string(), "! Some new feature");
+/// ```
+///
+/// With the `serde` feature a subject is serialized as an object tagged by `kind`, see
+/// [`Subject::kind_name`] for the possible values. Types are serialized by their
+/// [name](Type::as_str).
+///
+/// ```json
+/// {"kind":"conventional","type":"feat","scope":"ui","breaking":true,"description":"Foo","text":"feat(ui)!: Foo","pull_request":null}
+/// {"kind":"pull_request","id":"126","description":"Merge remote-tracking branch 'origin/pr/126'","text":"Merge remote-tracking branch 'origin/pr/126'","forge":"github","source":"pr/126","target":null,"remote":"origin","repository":null}
+/// {"kind":"merge","branches":["a","b"],"remote":null,"target":"main","description":"Merge branches 'a' and 'b' into main","text":"Merge branches 'a' and 'b' into main","pull_request":null}
+/// {"kind":"subtree","operation":"update","subtree":"foo","git_ref":"v1","description":"Update :foo to v1","text":"Update :foo to v1","pull_request":null}
+/// {"kind":"remove","description":"Remove Foo","text":"Remove Foo (#12)","pull_request":"12"}
 /// ```
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "serialize::SubjectRepr", from = "serialize::SubjectRepr")
+)]
 pub enum Subject {
     /// Conventaion Commit following the specification
     #[allow(missing_docs)]
@@ -122,136 +296,140 @@ pub enum Subject {
         category: Type,
         scope: Option<String>,
         description: String,
+        text: String,
+        pull_request: Option<String>,
     },
     /// Git fixup commit
-    Fixup(String),
-    /// A merged pull request
     #[allow(missing_docs)]
-    PullRequest { id: String, description: String },
+    Fixup {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    /// A branch merged with `git merge` or `git pull`, several for an octopus merge
+    ///
+    /// `remote` is the remote of merged remote-tracking branches or the repository pulled
+    /// from, `target` the branch merged into if it is not the default branch.
+    #[allow(missing_docs)]
+    Merge {
+        branches: Vec<String>,
+        remote: Option<String>,
+        target: Option<String>,
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    /// A merged pull request, `id` is `None` if the subject does not mention it
+    ///
+    /// `id` takes the place of the `pull_request` field of the other variants.
+    ///
+    /// `remote` is the git remote a pull request was fetched from, `repository` the slug
+    /// of the repository on the forge, e.g. `group/project`.
+    #[allow(missing_docs)]
+    PullRequest {
+        id: Option<String>,
+        description: String,
+        text: String,
+        forge: Forge,
+        source: Option<String>,
+        target: Option<String>,
+        remote: Option<String>,
+        repository: Option<String>,
+    },
     /// Commit releasing something
     #[allow(missing_docs)]
     Release {
         version: String,
         scope: Option<String>,
         description: String,
+        text: String,
+        pull_request: Option<String>,
     },
     /// Something removed
-    Remove(String),
+    #[allow(missing_docs)]
+    Remove {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
     /// Something renamed
-    Rename(String),
+    #[allow(missing_docs)]
+    Rename {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
     /// Commit created by `git-revert`
-    Revert(String),
+    #[allow(missing_docs)]
+    Revert {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
 
     /// A commit modifying a subtree tracked by`git-stree`.
     #[allow(missing_docs)]
     SubtreeCommit {
         operation: SubtreeOperation,
         description: String,
+        text: String,
+        pull_request: Option<String>,
     },
     /// Just some commit
-    Simple(String),
+    #[allow(missing_docs)]
+    Simple {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
 }
-//
 impl From<&str> for Subject {
     #[inline]
     fn from(subject: &str) -> Self {
-        #[allow(clippy::option_if_let_else)]
-        if let Some(caps) = RELEASE_REGEX1.captures(subject) {
-            Self::Release {
-                version: caps[2].to_owned(),
-                scope: Some(caps[1].to_owned()),
-                description: subject.to_owned(),
-            }
-        } else if let Some(caps) = RELEASE_REGEX2.captures(subject) {
-            Self::Release {
-                version: caps[1].to_owned(),
-                scope: None,
-                description: subject.to_owned(),
-            }
-        } else if let Some(caps) = PR_REGEX_AZURE.captures(subject) {
-            let id = caps[1].to_owned();
-            let description = format!("{} (#{})", &caps[2], id);
-            Self::PullRequest { id, description }
-        } else if let Some(caps) = PR_REGEX
-            .captures(subject)
-            .or_else(|| PR_REGEX_AZURE.captures(subject))
-            .or_else(|| PR_REGEX_BB.captures(subject))
-            .or_else(|| PR_REGEX_BORS.captures(subject))
-        {
-            Self::parse_pr(&caps, subject)
-        } else if subject.starts_with("fixup!") {
-            Self::Fixup(subject.to_owned())
-        } else if let Some(caps) = UPDATE_REGEX.captures(subject) {
-            let operation = SubtreeOperation::Update {
-                subtree: caps[1].to_owned(),
-                git_ref: caps[2].to_owned(),
-            };
-            Self::SubtreeCommit {
-                operation,
-                description: subject.to_owned(),
-            }
-        } else if let Some(caps) = IMPORT_REGEX.captures(subject) {
-            let operation = SubtreeOperation::Import {
-                subtree: caps[1].to_owned(),
-                git_ref: caps[2].to_owned(),
-            };
-            Self::SubtreeCommit {
-                operation,
-                description: subject.to_owned(),
-            }
-        } else if let Some(caps) = SPLIT_REGEX.captures(subject) {
-            let operation = SubtreeOperation::Split {
-                subtree: caps[1].to_owned(),
-                git_ref: caps[2].to_owned(),
-            };
-            Self::SubtreeCommit {
-                operation,
-                description: subject.to_owned(),
-            }
-        } else if subject.to_lowercase().starts_with("remove ") {
-            Self::Remove(subject.to_owned())
-        } else if subject.to_lowercase().starts_with("rename ")
-            || subject.to_lowercase().starts_with("move ")
-        {
-            Self::Rename(subject.to_owned())
-        } else if subject.to_lowercase().starts_with("revert ") {
-            Self::Revert(subject.to_owned())
-        } else if ADD_REGEX.is_match(subject) {
-            Self::ConventionalCommit {
-                breaking_change: false,
-                category: Type::Feat,
-                scope: None,
-                description: subject.to_owned(),
-            }
-        } else if FIX_REGEX.is_match(subject) {
-            Self::ConventionalCommit {
-                breaking_change: false,
-                category: Type::Fix,
-                scope: None,
-                description: subject.to_owned(),
-            }
-        } else if subject.to_lowercase().starts_with("deprecate ") {
-            Self::ConventionalCommit {
-                breaking_change: false,
-                category: Type::Deprecate,
-                scope: None,
-                description: subject.to_owned(),
-            }
-        } else if let Some(caps) = CONVENTIONAL_COMMIT_REGEX.captures(subject) {
-            Self::parse_conventional_commit(&caps)
-        } else {
-            Self::Simple(subject.to_owned())
-        }
+        classifier::DEFAULT.classify(subject)
+    }
+}
+
+/// Fallible classification, see [`Classifier::try_classify`]
+///
+/// ```rust
+/// use subject_classifier::{ClassifyError, Subject, Type};
+///
+/// let subject: Subject = "feat(ui): Add a button".parse().expect("Conventional commit");
+/// assert_eq!(subject.category(), Some(&Type::Feat));
+/// assert_eq!("[WIP] Foo".parse::<Subject>(), Err(ClassifyError::Unclassified));
+/// ```
+impl FromStr for Subject {
+    type Err = ClassifyError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        classifier::DEFAULT.try_classify(s)
+    }
+}
+
+/// Fallible classification, see [`Classifier::try_classify`]
+///
+/// `TryFrom<&str>` is provided by the infallible `From<&str>`, use [`str::parse`] instead.
+impl TryFrom<String> for Subject {
+    type Error = ClassifyError;
+
+    #[inline]
+    fn try_from(subject: String) -> Result<Self, Self::Error> {
+        subject.parse()
     }
 }
 
 impl Subject {
     /// Return a unicode character representing the subject
+    ///
+    /// Uses [Nerd Font](https://www.nerdfonts.com) v2 glyphs, see [`Subject::icon_with`] for
+    /// other themes. Every icon is two terminal cells wide.
     #[must_use]
     #[inline]
     pub const fn icon(&self) -> &str {
         match self {
-            Self::Fixup(_) => "\u{f0e3} ",
+            Self::Fixup { .. } => "\u{f0e3} ",
             Self::ConventionalCommit {
                 breaking_change,
                 category,
@@ -274,8 +452,8 @@ impl Subject {
                         Type::Issue => " ",
                         Type::Fix => "\u{f188} ",
                         Type::I18n => "\u{fac9}",
-                        Type::Other => "  ",
-                        Type::Perf => "\u{f9c4} ",
+                        Type::Other | Type::Custom(_) => "  ",
+                        Type::Perf => "\u{f9c4}",
                         Type::Refactor => "\u{f021} ",
                         Type::Repo => " ",
                         Type::Security => " ",
@@ -289,117 +467,233 @@ impl Subject {
                 SubtreeOperation::Split { .. } => "\u{f403} ",
                 SubtreeOperation::Update { .. } => "\u{f419} ",
             },
-            Self::Simple(_) => "  ",
+            Self::Simple { .. } => "  ",
             Self::Release { .. } => "\u{f412} ",
-            Self::Remove(_) => "\u{f48e} ",
-            Self::Rename(_) => "\u{f044} ",
-            Self::Revert(_) => " ",
+            Self::Remove { .. } => "\u{f48e} ",
+            Self::Rename { .. } => "\u{f044} ",
+            Self::Revert { .. } => " ",
             Self::PullRequest { .. } => " ",
+            Self::Merge { .. } => "\u{e727} ",
         }
     }
 
-    fn parse_pr(caps: &Captures<'_>, subject: &str) -> Self {
-        let id = if let Some(n) = caps.get(1) {
-            n.as_str().to_owned()
-        } else if let Some(n) = caps.get(2) {
-            n.as_str().to_owned()
-        } else {
-            // If we are here then something went completly wrong.
-            // to minimize the damage just return a `Subject::Simple`
-            return Self::Simple(subject.to_owned());
-        };
-        Self::PullRequest {
-            id,
-            description: subject.to_owned(),
-        }
+    /// Return the icon representing the subject in the given theme
+    ///
+    /// ```rust
+    /// use subject_classifier::icons::Theme;
+    /// use subject_classifier::Subject;
+    ///
+    /// assert_eq!(Subject::from("fix: Foo").icon_with(&Theme::Ascii).trim_end(), "[fix]");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn icon_with<'a, T>(&'a self, theme: &'a T) -> Cow<'a, str>
+    where
+        T: IconTheme + ?Sized,
+    {
+        theme.icon(self)
     }
 
-    fn parse_conventional_commit(caps: &Captures<'_>) -> Self {
-        let mut cat_text = caps[1].to_owned();
-        let mut scope_text = caps
-            .get(2)
-            .map_or_else(|| "".to_owned(), |_| caps[2].to_owned());
-        let mut rest_text = caps[3].to_owned();
-        let breaking_change = cat_text.ends_with('!')
-            || scope_text.ends_with('!')
-            || cat_text.to_lowercase().as_str() == "breaking change";
+    /// Classify a complete commit message.
+    ///
+    /// Like [`Subject::from`], but a `BREAKING CHANGE` footer marks a conventional commit
+    /// as breaking change. See [`CommitMessage`] for access to the body & footers.
+    ///
+    /// ```rust
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from_message("feat: Foo\n\nBREAKING-CHANGE: Bar is gone");
+    /// assert_eq!(subject.icon(), "⚠ ");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn from_message(message: &str) -> Self {
+        CommitMessage::from(message).subject
+    }
 
-        #[allow(clippy::arithmetic)]
+    pub(crate) fn mark_breaking_change(&mut self) {
+        if let Self::ConventionalCommit {
+            breaking_change, ..
+        } = self
         {
-            // arithmetic: if conditions guard the arithmetic
-            if cat_text.ends_with('!') {
-                cat_text.truncate(cat_text.len() - 1);
-            }
-            if scope_text.ends_with('!') {
-                scope_text.truncate(scope_text.len() - 1);
-            }
+            *breaking_change = true;
+        }
+    }
 
-            if scope_text.len() >= 3 {
-                scope_text = scope_text[1..scope_text.len() - 1].to_owned();
-            }
+    /// Complete a GitLab merge request with the `See merge request` line of the message body
+    pub(crate) fn set_merge_request(&mut self, value: &str, slug: &str) {
+        if let Self::PullRequest {
+            id,
+            forge: Forge::GitLab,
+            repository,
+            ..
+        } = self
+        {
+            *id = Some(value.to_owned());
+            *repository = Some(slug.to_owned());
         }
+    }
 
-        let scope = if scope_text.is_empty() {
-            None
-        } else {
-            Some(scope_text)
-        };
-
-        let category = match cat_text.to_lowercase().as_str() {
-            "archive" => Type::Archive,
-            "build" => Type::Build,
-            "breaking change" | "change" => Type::Change,
-            "chore" => Type::Chore,
-            "ci" => Type::Ci,
-            "deprecate" => Type::Deprecate,
-            "deps" => Type::Deps,
-            "dev" => Type::Dev,
-            "docs" => Type::Docs,
-            "add" | "feat" | "feature" => Type::Feat,
-            "bugfix" | "fix" | "hotfix" => Type::Fix,
-            "security" | "security fix" => Type::Security,
-            "i18n" => Type::I18n,
-            "gi" | "issue" | "done" => Type::Issue,
-            "improvement" => Type::Improvement,
-            "perf" => Type::Perf,
-            "internal" | "refactor" => Type::Refactor,
-            "repo" => Type::Repo,
-            "style" => Type::Style,
-            "test" | "tests" => Type::Test,
-            _ => Type::Other,
-        };
-
-        if category == Type::Other {
-            rest_text = caps[0].to_owned();
-        }
-        if breaking_change {
-            let mut tmp = "! ".to_owned();
-            tmp.push_str(&rest_text);
-            rest_text = tmp;
-        }
-
-        Self::ConventionalCommit {
-            breaking_change,
-            category,
-            scope,
-            description: rest_text,
-        }
-    }
-
-    /// Manipulated commit subject
+    /// Commit subject without type, scope, squash merge reference or other markup
+    ///
+    /// See [`Subject::decorated`] for the presentation with breaking change & pull request
+    /// markers.
+    ///
+    /// ```rust
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from("Remove Foo (#12)");
+    /// assert_eq!(subject.description(), "Remove Foo");
+    /// assert_eq!(subject.text(), "Remove Foo (#12)");
+    /// ```
     #[must_use]
     #[inline]
     pub fn description(&self) -> &str {
         match self {
             Self::ConventionalCommit { description, .. }
-            | Self::Fixup(description)
+            | Self::Fixup { description, .. }
+            | Self::Merge { description, .. }
             | Self::PullRequest { description, .. }
             | Self::Release { description, .. }
+            | Self::Remove { description, .. }
+            | Self::Rename { description, .. }
+            | Self::Revert { description, .. }
             | Self::SubtreeCommit { description, .. }
-            | Self::Remove(description)
-            | Self::Rename(description)
-            | Self::Revert(description)
-            | Self::Simple(description) => description,
+            | Self::Simple { description, .. } => description,
+        }
+    }
+
+    /// The untouched subject line
+    #[must_use]
+    #[inline]
+    pub fn text(&self) -> &str {
+        match self {
+            Self::ConventionalCommit { text, .. }
+            | Self::Fixup { text, .. }
+            | Self::Merge { text, .. }
+            | Self::PullRequest { text, .. }
+            | Self::Release { text, .. }
+            | Self::Remove { text, .. }
+            | Self::Rename { text, .. }
+            | Self::Revert { text, .. }
+            | Self::SubtreeCommit { text, .. }
+            | Self::Simple { text, .. } => text,
+        }
+    }
+
+    /// The description as shown to users
+    ///
+    /// Breaking changes are prefixed with `! `, pull request descriptions extracted from the
+    /// subject and squash merged commits are followed by the pull request id,
+    /// e.g. `Add Foo (#123)`.
+    ///
+    /// ```rust
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from("Merged PR 123: Add Foo");
+    /// assert_eq!(subject.description(), "Add Foo");
+    /// assert_eq!(subject.decorated().to_string(), "Add Foo (#123)");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub const fn decorated(&self) -> Decorated<'_> {
+        Decorated { subject: self }
+    }
+
+    /// The id of the pull request which merged the commit
+    ///
+    /// Either the id of a [`Subject::PullRequest`] or the reference GitHub appends to squash
+    /// merges, stored as `pull_request` when the subject is classified. The reference is not
+    /// part of the [description](Subject::description).
+    ///
+    /// ```rust
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from("feat(api): Add pagination (#4512)");
+    /// assert_eq!(subject.pull_request(), Some("4512"));
+    /// assert_eq!(subject.description(), "Add pagination");
+    /// assert_eq!(subject.decorated().to_string(), "Add pagination (#4512)");
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn pull_request(&self) -> Option<&str> {
+        match self {
+            Self::PullRequest { id, .. } => id.as_deref(),
+            Self::ConventionalCommit { pull_request, .. }
+            | Self::Fixup { pull_request, .. }
+            | Self::Merge { pull_request, .. }
+            | Self::Release { pull_request, .. }
+            | Self::Remove { pull_request, .. }
+            | Self::Rename { pull_request, .. }
+            | Self::Revert { pull_request, .. }
+            | Self::SubtreeCommit { pull_request, .. }
+            | Self::Simple { pull_request, .. } => pull_request.as_deref(),
+        }
+    }
+
+    /// The issue & ticket references in the subject line, see [`references::extract`]
+    ///
+    /// ```rust
+    /// use subject_classifier::references::ReferenceKind;
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from("fix: Crash on start, closes #9");
+    /// let references = subject.references();
+    /// assert_eq!(references[0].kind, ReferenceKind::Issue);
+    /// assert_eq!(references[0].id, "9");
+    /// assert!(references[0].closes);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn references(&self) -> Vec<Reference<'_>> {
+        references::extract(self.text())
+    }
+
+    /// The issue & ticket references in the subject line found by `extractor`
+    #[must_use]
+    #[inline]
+    pub fn references_with(&self, extractor: &Extractor) -> Vec<Reference<'_>> {
+        extractor.extract(self.text())
+    }
+
+    /// The type of a conventional commit
+    #[must_use]
+    #[inline]
+    pub const fn category(&self) -> Option<&Type> {
+        match self {
+            Self::ConventionalCommit { category, .. } => Some(category),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a breaking change
+    #[must_use]
+    #[inline]
+    pub const fn breaking_change(&self) -> bool {
+        matches!(
+            self,
+            Self::ConventionalCommit {
+                breaking_change: true,
+                ..
+            }
+        )
+    }
+
+    /// A short name of the variant, e.g. `conventional` or `pull_request`
+    #[must_use]
+    #[inline]
+    pub const fn kind_name(&self) -> &'static str {
+        match self {
+            Self::ConventionalCommit { .. } => "conventional",
+            Self::Fixup { .. } => "fixup",
+            Self::Merge { .. } => "merge",
+            Self::PullRequest { .. } => "pull_request",
+            Self::Release { .. } => "release",
+            Self::Remove { .. } => "remove",
+            Self::Rename { .. } => "rename",
+            Self::Revert { .. } => "revert",
+            Self::SubtreeCommit { .. } => "subtree",
+            Self::Simple { .. } => "simple",
         }
     }
 
@@ -417,11 +711,108 @@ impl Subject {
             _ => None,
         }
     }
+
+    /// The subject as normalized Conventional Commit line `type(scope)!: description`
+    ///
+    /// Returns `None` for subjects which are not conventional commits or have an unknown type.
+    /// Keyword heuristics get an explicit type.
+    ///
+    /// ```rust
+    /// use subject_classifier::Subject;
+    ///
+    /// let subject = Subject::from("BREAKING CHANGE: Drop Foo");
+    /// assert_eq!(subject.to_conventional_string().as_deref(), Some("change!: Drop Foo"));
+    /// let subject = Subject::from("Add a button");
+    /// assert_eq!(subject.to_conventional_string().as_deref(), Some("feat: Add a button"));
+    /// assert_eq!(Subject::from("Merge #12").to_conventional_string(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn to_conventional_string(&self) -> Option<String> {
+        match self {
+            Self::ConventionalCommit {
+                category: Type::Other,
+                ..
+            } => None,
+            Self::ConventionalCommit {
+                breaking_change,
+                category,
+                scope,
+                description,
+                ..
+            } => {
+                let mut result = category.as_str().to_owned();
+                if let Some(scope) = scope {
+                    result.push('(');
+                    result.push_str(scope);
+                    result.push(')');
+                }
+                if *breaking_change {
+                    result.push('!');
+                }
+                result.push_str(": ");
+                result.push_str(description);
+                if let Some(id) = self.pull_request() {
+                    result.push_str(" (#");
+                    result.push_str(id);
+                    result.push(')');
+                }
+                Some(result)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Subject {
+    /// Writes [`Subject::to_conventional_string`] or the [`Subject::text`] for subjects
+    /// without a conventional form
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_conventional_string() {
+            Some(line) => f.write_str(&line),
+            None => f.write_str(self.text()),
+        }
+    }
+}
+
+/// Presentation of a [`Subject`]'s description, see [`Subject::decorated`]
+#[derive(Clone, Copy, Debug)]
+pub struct Decorated<'a> {
+    subject: &'a Subject,
+}
+
+impl fmt::Display for Decorated<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.subject {
+            Subject::PullRequest {
+                id: Some(id),
+                description,
+                text,
+                ..
+            } if description != text => {
+                write!(f, "{} (#{})", description, id)
+            }
+            Subject::PullRequest { description, .. } => f.write_str(description),
+            subject => {
+                if subject.breaking_change() {
+                    f.write_str("! ")?;
+                }
+                f.write_str(subject.description())?;
+                match subject.pull_request() {
+                    Some(id) => write!(f, " (#{})", id),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Subject, SubtreeOperation, Type};
+    use crate::rules::Derivation;
+    use crate::{Classifier, ClassifyError, Forge, Subject, SubtreeOperation, Type};
 
     #[test]
     fn archive() {
@@ -434,6 +825,8 @@ mod tests {
                 category: Type::Archive,
                 scope: None,
                 description,
+                text: "archive: windowmanager".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -449,6 +842,8 @@ mod tests {
                 category: Type::Build,
                 scope: Some("repo".to_owned()),
                 description,
+                text: "build(repo): Always use local file-expert".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -457,7 +852,7 @@ mod tests {
     fn change() {
         {
             let result = Subject::from("change!: Replace strncpy with memcpy");
-            let description = "! Replace strncpy with memcpy".to_owned();
+            let description = "Replace strncpy with memcpy".to_owned();
             assert_eq!(
                 result,
                 Subject::ConventionalCommit {
@@ -465,6 +860,8 @@ mod tests {
                     category: Type::Change,
                     scope: None,
                     description,
+                    text: "change!: Replace strncpy with memcpy".to_owned(),
+                    pull_request: None,
                 },
             );
             assert_eq!(result.icon(), "⚠ ");
@@ -479,6 +876,8 @@ mod tests {
                     category: Type::Change,
                     scope: None,
                     description: description.clone(),
+                    text: "change: Replace strncpy with memcpy".to_owned(),
+                    pull_request: None,
                 },
             );
             assert_eq!(result.description(), description);
@@ -495,6 +894,8 @@ mod tests {
                     category: Type::Change,
                     scope: None,
                     description: description.clone(),
+                    text: "CHANGE Replace strncpy with memcpy".to_owned(),
+                    pull_request: None,
                 },
             );
             assert_eq!(result.description(), description);
@@ -505,7 +906,7 @@ mod tests {
     #[test]
     fn breaking_change() {
         let result = Subject::from("breaking change: Commits are now namedtupples");
-        let description = "! Commits are now namedtupples".to_owned();
+        let description = "Commits are now namedtupples".to_owned();
         assert_eq!(
             result,
             Subject::ConventionalCommit {
@@ -513,6 +914,8 @@ mod tests {
                 category: Type::Change,
                 scope: None,
                 description: description.clone(),
+                text: "breaking change: Commits are now namedtupples".to_owned(),
+                pull_request: None,
             },
         );
         assert_eq!(result.description(), description);
@@ -530,6 +933,8 @@ mod tests {
                 category: Type::Ci,
                 scope: Some("srht".to_owned()),
                 description,
+                text: "ci(srht): Fedora Rawhide run dist-rpm && qubes-builder".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -544,6 +949,8 @@ mod tests {
                 category: Type::Deps,
                 scope: None,
                 description,
+                text: "deps: Use thick Xlib bindings".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -558,6 +965,8 @@ mod tests {
                 category: Type::Docs,
                 scope: Some("readme".to_owned()),
                 description,
+                text: "docs(readme): add xcb-util-xrm to dependencies' list".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -573,6 +982,8 @@ mod tests {
                 category: Type::Refactor,
                 scope: None,
                 description,
+                text: "internal: Move mismatched arg count diagnostic to inference".to_owned(),
+                pull_request: None,
             },
         );
     }
@@ -580,7 +991,7 @@ mod tests {
     #[test]
     fn scope_breaking_change() {
         let result = Subject::from("fix(search)!: This breaks the api");
-        let description = "! This breaks the api".to_owned();
+        let description = "This breaks the api".to_owned();
         assert_eq!(
             result,
             Subject::ConventionalCommit {
@@ -588,6 +999,8 @@ mod tests {
                 category: Type::Fix,
                 scope: Some("search".to_owned()),
                 description,
+                text: "fix(search)!: This breaks the api".to_owned(),
+                pull_request: None,
             },
         );
         assert_eq!(result.icon(), "⚠ ");
@@ -604,9 +1017,14 @@ mod tests {
                     subtree: "qubes-builder".to_owned(),
                     git_ref: "5e5301b8eac".to_owned()
                 },
-                description: text.to_owned()
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
             }
         );
+        let classification = Classifier::default().explain(text);
+        assert_eq!(classification.derivation, Derivation::Explicit);
+        assert!(classification.confidence() > Derivation::Merge.confidence());
     }
 
     #[test]
@@ -620,9 +1038,14 @@ mod tests {
                     subtree: "rust".to_owned(),
                     git_ref: "baa77665cab9b8b25c7887e021280d8b55e2c9cb".to_owned()
                 },
-                description: text.to_owned()
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
             }
         );
+        let classification = Classifier::default().explain(text);
+        assert_eq!(classification.derivation, Derivation::Explicit);
+        assert!(classification.confidence() > Derivation::Merge.confidence());
     }
 
     #[test]
@@ -636,9 +1059,14 @@ mod tests {
                     subtree: "php/composer-monorepo-plugin".to_owned(),
                     git_ref: "master".to_owned()
                 },
-                description: text.to_owned()
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
             }
         );
+        let classification = Classifier::default().explain(text);
+        assert_eq!(classification.derivation, Derivation::Explicit);
+        assert!(classification.confidence() > Derivation::Merge.confidence());
     }
 
     #[test]
@@ -650,11 +1078,24 @@ mod tests {
             Subject::Release {
                 version: "2.11.0".to_owned(),
                 scope: Some("foo".to_owned()),
-                description: text.to_owned()
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
             }
         );
     }
 
+    #[test]
+    fn dependency_bump() {
+        for text in [
+            "Bump regex from 1.5.4 to 1.5.5",
+            "Bump @types/node from 18.0.0 to 18.0.1",
+        ] {
+            let result = Subject::from(text);
+            assert!(!matches!(result, Subject::Release { .. }), "{:?}", result);
+        }
+    }
+
     #[test]
     fn release2() {
         {
@@ -665,11 +1106,19 @@ mod tests {
                 Subject::Release {
                     version: "2.11.0".to_owned(),
                     scope: None,
-                    description: text.to_owned()
+                    description: text.to_owned(),
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
 
+        {
+            let text = "Bump version from 2.10.0 to 2.11.0";
+            let result = Subject::from(text);
+            assert!(matches!(result, Subject::Release { .. }), "{:?}", result);
+        }
+
         {
             let text = "Release 2.11.0";
             let result = Subject::from(text);
@@ -678,7 +1127,9 @@ mod tests {
                 Subject::Release {
                     version: "2.11.0".to_owned(),
                     scope: None,
-                    description: text.to_owned()
+                    description: text.to_owned(),
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
@@ -688,14 +1139,28 @@ mod tests {
     fn revert() {
         let text = "Revert two commits breaking watching hotplug-status xenstore node";
         let result = Subject::from(text);
-        assert_eq!(result, Subject::Revert(text.to_owned()));
+        assert_eq!(
+            result,
+            Subject::Revert {
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
+            }
+        );
     }
 
     #[test]
     fn rename() {
         let text = "Rename ForkPointCalculation::Needed → InProgress";
         let result = Subject::from(text);
-        assert_eq!(result, Subject::Rename(text.to_owned()));
+        assert_eq!(
+            result,
+            Subject::Rename {
+                description: text.to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
+            }
+        );
     }
 
     #[test]
@@ -705,8 +1170,33 @@ mod tests {
         assert_eq!(
             result,
             Subject::PullRequest {
-                id: "126".to_owned(),
-                description: text.to_owned()
+                id: Some("126".to_owned()),
+                description: text.to_owned(),
+                text: text.to_owned(),
+                forge: Forge::GitHub,
+                source: Some("pr/126".to_owned()),
+                target: None,
+                remote: Some("origin".to_owned()),
+                repository: None,
+            }
+        );
+    }
+
+    #[test]
+    fn pr_github() {
+        let text = "Merge pull request #12 from foo/bar";
+        let result = Subject::from(text);
+        assert_eq!(
+            result,
+            Subject::PullRequest {
+                id: Some("12".to_owned()),
+                description: text.to_owned(),
+                text: text.to_owned(),
+                forge: Forge::GitHub,
+                source: Some("foo/bar".to_owned()),
+                target: None,
+                remote: None,
+                repository: None,
             }
         );
     }
@@ -718,8 +1208,14 @@ mod tests {
         assert_eq!(
             result,
             Subject::PullRequest {
-                id: "7771".to_owned(),
-                description: text.to_owned()
+                id: Some("7771".to_owned()),
+                description: text.to_owned(),
+                text: text.to_owned(),
+                forge: Forge::Bitbucket,
+                source: Some("feature/asdqwert".to_owned()),
+                target: Some("development".to_owned()),
+                remote: None,
+                repository: Some("FOO/bar".to_owned()),
             }
         );
     }
@@ -731,10 +1227,177 @@ mod tests {
         assert_eq!(
             result,
             Subject::PullRequest {
-                id: "36587".to_owned(),
-                description: "Add Foo calibration to item type (#36587)".to_owned()
+                id: Some("36587".to_owned()),
+                description: "Add Foo calibration to item type".to_owned(),
+                text: text.to_owned(),
+                forge: Forge::Azure,
+                source: None,
+                target: None,
+                remote: None,
+                repository: None,
+            }
+        );
+    }
+
+    #[test]
+    fn pr_bors() {
+        let result = Subject::from("Merge #123");
+        assert!(matches!(
+            result,
+            Subject::PullRequest { id: Some(id), forge: Forge::Bors, .. } if id == "123"
+        ));
+    }
+
+    #[test]
+    fn pr_gitlab() {
+        let text = "Merge branch 'feature/x' into 'main'";
+        let result = Subject::from(text);
+        assert_eq!(
+            result,
+            Subject::PullRequest {
+                id: None,
+                description: text.to_owned(),
+                text: text.to_owned(),
+                forge: Forge::GitLab,
+                source: Some("feature/x".to_owned()),
+                target: Some("main".to_owned()),
+                remote: None,
+                repository: None,
+            }
+        );
+        assert_eq!(result.decorated().to_string(), text);
+        assert_eq!(result.pull_request(), None);
+        let result = Subject::from_message(&format!("{}\n\nSee merge request a/b!7\n", text));
+        assert!(matches!(result, Subject::PullRequest { id: Some(id), .. } if id == "7"));
+    }
+
+    #[test]
+    fn pr_gerrit() {
+        let text = "Merge \"Add Foo\" into stable-1.0";
+        let result = Subject::from(text);
+        assert_eq!(
+            result,
+            Subject::PullRequest {
+                id: None,
+                description: "Add Foo".to_owned(),
+                text: text.to_owned(),
+                forge: Forge::Gerrit,
+                source: None,
+                target: Some("stable-1.0".to_owned()),
+                remote: None,
+                repository: None,
             }
         );
+        assert_eq!(result.decorated().to_string(), "Add Foo");
+    }
+
+    #[test]
+    fn merge() {
+        for (text, branches, remote, target) in [
+            ("Merge branch 'develop'", vec!["develop"], None, None),
+            (
+                "Merge branch 'develop' into main",
+                vec!["develop"],
+                None,
+                Some("main"),
+            ),
+            (
+                "Merge branch 'main' of github.com:foo/bar",
+                vec!["main"],
+                Some("github.com:foo/bar"),
+                None,
+            ),
+            (
+                "Merge remote-tracking branch 'origin/main'",
+                vec!["main"],
+                Some("origin"),
+                None,
+            ),
+            (
+                "Merge remote-tracking branches 'origin/a' and 'origin/b' into c",
+                vec!["a", "b"],
+                Some("origin"),
+                Some("c"),
+            ),
+            (
+                "Merge branches 'a', 'b' and 'c'",
+                vec!["a", "b", "c"],
+                None,
+                None,
+            ),
+        ] {
+            assert_eq!(
+                Subject::from(text),
+                Subject::Merge {
+                    branches: branches.into_iter().map(str::to_owned).collect(),
+                    remote: remote.map(str::to_owned),
+                    target: target.map(str::to_owned),
+                    description: text.to_owned(),
+                    text: text.to_owned(),
+                    pull_request: None,
+                },
+                "{}",
+                text
+            );
+        }
+        assert!(matches!(
+            Subject::from("Merge branch 'feature/x' into 'main'"),
+            Subject::PullRequest { .. }
+        ));
+    }
+
+    #[test]
+    fn squash_merge() {
+        let text = "feat(api)!: Add pagination (#4512)";
+        let result = Subject::from(text);
+        assert_eq!(
+            result,
+            Subject::ConventionalCommit {
+                breaking_change: true,
+                category: Type::Feat,
+                scope: Some("api".to_owned()),
+                description: "Add pagination".to_owned(),
+                text: text.to_owned(),
+                pull_request: Some("4512".to_owned()),
+            }
+        );
+        assert_eq!(result.pull_request(), Some("4512"));
+        assert_eq!(result.decorated().to_string(), "! Add pagination (#4512)");
+        assert_eq!(result.to_string(), "feat(api)!: Add pagination (#4512)");
+
+        for (text, id) in [
+            ("Add Foo (#12)", Some("12")),
+            ("Remove Foo (#12)", Some("12")),
+            ("Merged PR 36587: Add Foo", Some("36587")),
+            ("Merge branch 'feature/x' into 'main'", None),
+            ("fix: Foo (#12) bar", None),
+            ("fix: Foo(#12)", None),
+        ] {
+            assert_eq!(Subject::from(text).pull_request(), id, "{}", text);
+        }
+        assert_eq!(Subject::from("Add Foo (#12)").description(), "Add Foo");
+        assert_eq!(
+            Subject::from("Remove Foo (#12)"),
+            Subject::Remove {
+                description: "Remove Foo".to_owned(),
+                text: "Remove Foo (#12)".to_owned(),
+                pull_request: Some("12".to_owned()),
+            }
+        );
+        for text in [
+            "Remove Foo (#12)",
+            "Rename Foo (#12)",
+            "Revert \"Foo\" (#12)",
+            "fixup! Foo (#12)",
+            "Release v1.0.0 (#12)",
+            "Some commit (#12)",
+        ] {
+            let result = Subject::from(text);
+            assert_eq!(result.description(), &text[..text.len() - 6], "{}", text);
+            assert_eq!(result.text(), text);
+            assert_eq!(result.pull_request(), Some("12"), "{}", text);
+            assert_eq!(result.decorated().to_string(), text);
+        }
     }
 
     #[test]
@@ -749,7 +1412,9 @@ mod tests {
                     breaking_change: false,
                     category: Type::Security,
                     scope: None,
-                    description
+                    description,
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
@@ -764,7 +1429,9 @@ mod tests {
                     breaking_change: false,
                     category: Type::Security,
                     scope: None,
-                    description
+                    description,
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
@@ -780,7 +1447,9 @@ mod tests {
                 breaking_change: false,
                 category: Type::Other,
                 scope: None,
-                description: "Makefile: replace '-' in plugins_var".to_owned()
+                description: "Makefile: replace '-' in plugins_var".to_owned(),
+                text: text.to_owned(),
+                pull_request: None,
             }
         );
     }
@@ -797,7 +1466,9 @@ mod tests {
                     breaking_change: false,
                     category: Type::Deprecate,
                     scope: None,
-                    description
+                    description,
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
@@ -811,9 +1482,165 @@ mod tests {
                     breaking_change: false,
                     category: Type::Deprecate,
                     scope: None,
-                    description
+                    description,
+                    text: text.to_owned(),
+                    pull_request: None,
                 }
             );
         }
     }
+
+    /// Subjects used in the tests above
+    const INPUTS: &[&str] = &[
+        "archive: windowmanager",
+        "BREAKING CHANGE: Commits are now namedtupples",
+        "build(repo): Always use ./.git/modules as modules dir",
+        "ci(srht): Fedora Rawhide run dist-rpm && qubes-template",
+        "deps: Use thiserror instead of anyhow",
+        "docs(readme): add xcb-util-xrm to dependencies' list",
+        "fix(search)!: This breaks the api",
+        "fix: Do not crash on empty input",
+        "feat(ui): Add a button",
+        "Add a button",
+        "Fix crash on startup",
+        "Deprecate Foo",
+        "Makefile: replace '-' in plugins_var",
+        "fixup! feat: Foo",
+        "Merge pull request #12 from foo/bar",
+        "Merge branches 'a' and 'b' into main",
+        "Release foo@v2.11.0",
+        "Remove Foo",
+        "Rename Foo to Bar",
+        "Revert \"feat: Foo\"",
+        "Update :qubes-builder to 5e5301b8eac",
+        "[WIP] Foo",
+    ];
+
+    #[test]
+    fn conventional_round_trip() {
+        let corpus = include_str!("../benches/subjects.txt").lines();
+        for text in INPUTS.iter().copied().chain(corpus) {
+            let subject = Subject::from(text);
+            if let Some(line) = subject.to_conventional_string() {
+                let parsed = Subject::from(line.as_str());
+                assert_eq!(parsed.category(), subject.category(), "{} → {}", text, line);
+                assert_eq!(parsed.scope(), subject.scope(), "{} → {}", text, line);
+                assert_eq!(parsed.description(), subject.description());
+                assert_eq!(parsed.breaking_change(), subject.breaking_change());
+                assert_eq!(subject.to_string(), line);
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_type_before_heuristic() {
+        assert_eq!(
+            Subject::from("fix(ui): Align icon"),
+            Subject::ConventionalCommit {
+                breaking_change: false,
+                category: Type::Fix,
+                scope: Some("ui".to_owned()),
+                description: "Align icon".to_owned(),
+                text: "fix(ui): Align icon".to_owned(),
+                pull_request: None,
+            }
+        );
+        assert_eq!(Subject::from("add: Foo").description(), "Foo");
+        assert_eq!(Subject::from("Fixed: Foo").description(), "Fixed: Foo");
+    }
+
+    #[test]
+    fn normalize() {
+        for (text, expected) in [
+            (
+                "FIX(search)!: This breaks the api",
+                "fix(search)!: This breaks the api",
+            ),
+            ("Feature: Foo", "feat: Foo"),
+            ("BREAKING CHANGE: Foo", "change!: Foo"),
+            ("hotfix/Foo", "fix: Foo"),
+            ("Add a button", "feat: Add a button"),
+        ] {
+            assert_eq!(
+                Subject::from(text).to_conventional_string().as_deref(),
+                Some(expected),
+                "{}",
+                text
+            );
+        }
+        assert_eq!(
+            Subject::from("Makefile: replace '-' in plugins_var").to_conventional_string(),
+            None
+        );
+    }
+
+    #[test]
+    fn display() {
+        for text in [
+            "Makefile: replace '-' in plugins_var",
+            "fixup! feat: Foo",
+            "Merge pull request #12 from foo/bar",
+            "Merge branch 'develop' into main",
+            "Release foo@v2.11.0",
+            "Remove Foo",
+            "Rename Foo to Bar",
+            "Revert \"feat: Foo\"",
+            "Update :qubes-builder to 5e5301b8eac",
+            "[WIP] Foo",
+        ] {
+            let subject = Subject::from(text);
+            assert_eq!(subject.to_string(), text);
+            assert_eq!(Subject::from(subject.to_string().as_str()), subject);
+        }
+        assert_eq!(
+            Subject::from("docs(readme): Foo").to_string(),
+            "docs(readme): Foo"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "Remove Foo".parse(),
+            Ok(Subject::Remove {
+                description: "Remove Foo".to_owned(),
+                text: "Remove Foo".to_owned(),
+                pull_request: None,
+            })
+        );
+        assert_eq!(
+            Subject::try_from("Merge #12".to_owned()),
+            Ok(Subject::from("Merge #12"))
+        );
+        assert_eq!("".parse::<Subject>(), Err(ClassifyError::Empty));
+        assert_eq!(
+            "Initial commit".parse::<Subject>(),
+            Err(ClassifyError::Unclassified)
+        );
+        assert_eq!(
+            Subject::try_from("foo(bar): Baz".to_owned()),
+            Err(ClassifyError::UnknownType("foo".to_owned()))
+        );
+    }
+
+    #[test]
+    fn text_and_decorated() {
+        for (text, description, decorated) in [
+            ("feat(ui)!: Foo", "Foo", "! Foo"),
+            ("BREAKING CHANGE: Foo", "Foo", "! Foo"),
+            ("fix: Foo", "Foo", "Foo"),
+            ("Merged PR 123: Foo", "Foo", "Foo (#123)"),
+            (
+                "Merge pull request #12 from foo/bar",
+                "Merge pull request #12 from foo/bar",
+                "Merge pull request #12 from foo/bar",
+            ),
+            ("Remove Foo", "Remove Foo", "Remove Foo"),
+        ] {
+            let subject = Subject::from(text);
+            assert_eq!(subject.text(), text);
+            assert_eq!(subject.description(), description, "{}", text);
+            assert_eq!(subject.decorated().to_string(), decorated, "{}", text);
+        }
+    }
 }
@@ -0,0 +1,288 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of complete commit messages (subject, body & footers)
+
+use regex::Regex;
+
+use once_cell::sync::Lazy;
+
+use crate::classifier::DEFAULT;
+use crate::{Classifier, Subject};
+
+regex!(FOOTER_REGEX, r"^(BREAKING[ -]CHANGE|[\w-]+)(?:: | #)(.*)$");
+regex!(MERGE_REQUEST_REGEX, r"^See merge request (\S+)!(\d+)$");
+
+/// A footer (git trailer) of a commit message, e.g. `Refs: #123`
+///
+/// ```rust
+/// use subject_classifier::CommitMessage;
+///
+/// let msg = CommitMessage::from("fix: Foo\n\nCloses #123");
+/// assert_eq!(msg.footers[0].token, "Closes");
+/// assert_eq!(msg.footers[0].value, "123");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Footer {
+    /// The footer token, e.g. `Refs` or `BREAKING CHANGE`
+    pub token: String,
+    /// The footer value, may span multiple lines
+    pub value: String,
+}
+
+impl Footer {
+    /// Returns `true` if this is a `BREAKING CHANGE` or `BREAKING-CHANGE` footer
+    #[must_use]
+    #[inline]
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+}
+
+/// A complete commit message following the [Conventional Commits Standard v1.0.0](https://www.conventionalcommits.org/en/v1.0.0)
+///
+/// ```rust
+/// use subject_classifier::{CommitMessage, Subject};
+///
+/// let msg = CommitMessage::from("feat: Foo\n\nSome body\n\nBREAKING CHANGE: Bar is gone");
+/// assert_eq!(msg.body.as_deref(), Some("Some body"));
+/// assert!(msg.breaking_change());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitMessage {
+    /// The classified first line
+    pub subject: Subject,
+    /// Free form text between the subject and the footers
+    pub body: Option<String>,
+    /// Footers in the order they appear in the message
+    pub footers: Vec<Footer>,
+}
+
+impl From<&str> for CommitMessage {
+    #[inline]
+    fn from(message: &str) -> Self {
+        Self::parse(message, &DEFAULT)
+    }
+}
+
+impl CommitMessage {
+    pub(crate) fn parse(message: &str, classifier: &Classifier) -> Self {
+        let mut lines = message.lines();
+        let subject_line = lines.next().unwrap_or_default().trim_end();
+        let rest: Vec<&str> = lines.map(str::trim_end).collect();
+
+        let footer_start = footer_block_start(&rest);
+        let footers = parse_footers(&rest[footer_start..]);
+        let body = {
+            let text = rest[..footer_start].join("\n");
+            let text = text.trim_matches('\n');
+            if text.is_empty() {
+                None
+            } else {
+                Some(text.to_owned())
+            }
+        };
+
+        let mut subject = classifier.classify(subject_line);
+        if footers.iter().any(Footer::is_breaking_change) {
+            subject.mark_breaking_change();
+        }
+        if let Some(caps) = rest.iter().find_map(|l| MERGE_REQUEST_REGEX.captures(l)) {
+            subject.set_merge_request(&caps[2], &caps[1]);
+        }
+
+        Self {
+            subject,
+            body,
+            footers,
+        }
+    }
+
+    /// Returns the first footer with the given token
+    #[must_use]
+    #[inline]
+    pub fn footer(&self, token: &str) -> Option<&Footer> {
+        self.footers.iter().find(|f| f.token == token)
+    }
+
+    /// Returns `true` if either the subject or a footer marks a breaking change
+    #[must_use]
+    #[inline]
+    pub fn breaking_change(&self) -> bool {
+        self.subject.breaking_change() || self.footers.iter().any(Footer::is_breaking_change)
+    }
+}
+
+/// Index of the first line of the footer block or `lines.len()` if there is none.
+///
+/// The footer block is the last paragraph, if its first line is a footer.
+fn footer_block_start(lines: &[&str]) -> usize {
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(0, |i| i + 1);
+    let start = lines[..end]
+        .iter()
+        .rposition(|l| l.is_empty())
+        .map_or(0, |i| i + 1);
+    if start < end && FOOTER_REGEX.is_match(lines[start]) {
+        start
+    } else {
+        lines.len()
+    }
+}
+
+fn parse_footers(lines: &[&str]) -> Vec<Footer> {
+    let mut result: Vec<Footer> = vec![];
+    for line in lines {
+        if let Some(caps) = FOOTER_REGEX.captures(line) {
+            result.push(Footer {
+                token: caps[1].to_owned(),
+                value: caps[2].to_owned(),
+            });
+        } else if let Some(last) = result.last_mut() {
+            last.value.push('\n');
+            last.value.push_str(line);
+        }
+    }
+    for footer in &mut result {
+        let len = footer.value.trim_end().len();
+        footer.value.truncate(len);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CommitMessage, Footer, Forge, Subject, Type};
+
+    #[test]
+    fn subject_only() {
+        let result = CommitMessage::from("fix: Foo");
+        assert_eq!(
+            result,
+            CommitMessage {
+                subject: Subject::from("fix: Foo"),
+                body: None,
+                footers: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn body() {
+        let result =
+            CommitMessage::from("fix: Foo\n\nFirst paragraph\n\nSecond paragraph: x\nfoo\n");
+        assert_eq!(
+            result.body.as_deref(),
+            Some("First paragraph\n\nSecond paragraph: x\nfoo")
+        );
+        assert!(result.footers.is_empty());
+    }
+
+    #[test]
+    fn footers() {
+        let text = "fix: Foo\n\nSome body\n\nReviewed-by: Z\nRefs #133\nCloses: #1";
+        let result = CommitMessage::from(text);
+        assert_eq!(result.body.as_deref(), Some("Some body"));
+        assert_eq!(
+            result.footers,
+            vec![
+                Footer {
+                    token: "Reviewed-by".to_owned(),
+                    value: "Z".to_owned()
+                },
+                Footer {
+                    token: "Refs".to_owned(),
+                    value: "133".to_owned()
+                },
+                Footer {
+                    token: "Closes".to_owned(),
+                    value: "#1".to_owned()
+                },
+            ]
+        );
+        assert_eq!(result.footer("Refs").map(|f| f.value.as_str()), Some("133"));
+        assert!(!result.breaking_change());
+    }
+
+    #[test]
+    fn multi_line_footer() {
+        let text = "feat: Foo\n\nBREAKING CHANGE: first line\n  second line\nRefs: #1\n";
+        let result = CommitMessage::from(text);
+        assert_eq!(result.body, None);
+        assert_eq!(result.footers[0].value, "first line\n  second line");
+        assert_eq!(result.footers[1].value, "#1");
+    }
+
+    #[test]
+    fn breaking_change_footer() {
+        for token in ["BREAKING CHANGE", "BREAKING-CHANGE"] {
+            let text = format!("feat(api): Drop Foo\n\n{}: Foo is gone", token);
+            let result = CommitMessage::from(text.as_str());
+            assert_eq!(
+                result.subject,
+                Subject::ConventionalCommit {
+                    breaking_change: true,
+                    category: Type::Feat,
+                    scope: Some("api".to_owned()),
+                    description: "Drop Foo".to_owned(),
+                    text: "feat(api): Drop Foo".to_owned(),
+                    pull_request: None,
+                }
+            );
+            assert!(result.breaking_change());
+        }
+    }
+
+    #[test]
+    fn from_message() {
+        let result = Subject::from_message("feat!: Foo\n\nBREAKING CHANGE: Bar");
+        assert_eq!(
+            result,
+            Subject::ConventionalCommit {
+                breaking_change: true,
+                category: Type::Feat,
+                scope: None,
+                description: "Foo".to_owned(),
+                text: "feat!: Foo".to_owned(),
+                pull_request: None,
+            }
+        );
+    }
+
+    #[test]
+    fn gitlab_merge_request() {
+        let text = "Merge branch 'feature/x' into 'main'\n\nAdd Foo\n\nSee merge request group/project!123";
+        let result = CommitMessage::from(text);
+        assert_eq!(
+            result.subject,
+            Subject::PullRequest {
+                id: Some("123".to_owned()),
+                description: "Merge branch 'feature/x' into 'main'".to_owned(),
+                text: "Merge branch 'feature/x' into 'main'".to_owned(),
+                forge: Forge::GitLab,
+                source: Some("feature/x".to_owned()),
+                target: Some("main".to_owned()),
+                remote: None,
+                repository: Some("group/project".to_owned()),
+            }
+        );
+        let result = CommitMessage::from("Merge #12\n\nSee merge request group/project!123");
+        assert_eq!(result.subject, Subject::from("Merge #12"));
+    }
+}
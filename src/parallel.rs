@@ -0,0 +1,94 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parallel classification using [rayon](https://docs.rs/rayon)
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+use crate::classifier::DEFAULT;
+use crate::{Classifier, Subject};
+
+impl Classifier {
+    /// Classify many subjects in parallel
+    ///
+    /// The returned iterator is indexed, collecting it preserves the input order.
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use subject_classifier::{Classifier, Subject};
+    ///
+    /// let log = vec!["feat: Foo", "Release v1.0.0"];
+    /// let subjects: Vec<Subject> = Classifier::default().par_classify_all(log).collect();
+    /// assert!(matches!(subjects[1], Subject::Release { .. }));
+    /// ```
+    #[inline]
+    pub fn par_classify_all<'c, I>(
+        &'c self,
+        subjects: I,
+    ) -> impl IndexedParallelIterator<Item = Subject> + 'c
+    where
+        I: IntoParallelIterator + 'c,
+        I::Iter: IndexedParallelIterator + 'c,
+        I::Item: AsRef<str>,
+    {
+        subjects
+            .into_par_iter()
+            .map(move |subject| self.classify(subject.as_ref()))
+    }
+}
+
+/// Classify many subjects in parallel with the default [`Classifier`], see
+/// [`Classifier::par_classify_all`]
+#[inline]
+pub fn par_classify_all<'a, I>(subjects: I) -> impl IndexedParallelIterator<Item = Subject> + 'a
+where
+    I: IntoParallelIterator + 'a,
+    I::Iter: IndexedParallelIterator + 'a,
+    I::Item: AsRef<str>,
+{
+    DEFAULT.par_classify_all(subjects)
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use crate::{par_classify_all, Classifier, Subject, Type};
+
+    #[test]
+    fn input_order() {
+        let subjects: Vec<&str> = include_str!("../benches/subjects.txt").lines().collect();
+        let expected: Vec<Subject> = subjects.iter().copied().map(Subject::from).collect();
+        let result: Vec<Subject> = par_classify_all(subjects.par_iter()).collect();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn owned() {
+        let classifier = Classifier::builder().category("ops").build();
+        let subjects = vec!["ops: Foo".to_owned(); 1000];
+        let result: Vec<Subject> = classifier.par_classify_all(subjects).collect();
+        assert_eq!(result.len(), 1000);
+        assert!(result.iter().all(|s| matches!(
+            s,
+            Subject::ConventionalCommit {
+                category: Type::Custom(_),
+                ..
+            }
+        )));
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Commit message policies, e.g. for a `commit-msg` git hook
+//!
+//! ```rust
+//! use subject_classifier::policy::{Policy, Violation};
+//! use subject_classifier::Type;
+//!
+//! let policy = Policy {
+//!     require_scope: true,
+//!     allowed_types: vec![Type::Feat, Type::Fix],
+//!     ..Policy::default()
+//! };
+//! assert!(policy.check("feat(ui): Dark mode").is_empty());
+//! assert_eq!(policy.check("docs: Foo"), vec![
+//!     Violation::MissingScope,
+//!     Violation::DisallowedType("docs".to_owned()),
+//! ]);
+//! assert_eq!(policy.check("Makefile: Foo"), vec![Violation::UnknownType]);
+//! ```
+
+use std::fmt;
+
+use crate::classifier::DEFAULT;
+use crate::{Classifier, Subject, Type};
+
+/// Git's scissors line, everything below it is removed from the commit message
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// A reason to reject a commit message
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Violation {
+    /// The subject is empty
+    EmptySubject,
+    /// The subject could not be classified, i.e. it is a [`Subject::Simple`]
+    Unclassified,
+    /// The type of a conventional commit is unknown, i.e. [`Type::Other`]
+    UnknownType,
+    /// The conventional commit has no scope, but the policy requires one
+    MissingScope,
+    /// The scope is not in [`Policy::scopes`]
+    UnknownScope(String),
+    /// The type of a conventional commit is not in [`Policy::allowed_types`]
+    DisallowedType(String),
+    /// The subject exceeds [`Policy::max_length`]
+    TooLong {
+        /// Length of the subject in characters
+        length: usize,
+        /// Maximum allowed length
+        max: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySubject => f.write_str("empty subject"),
+            Self::Unclassified => f.write_str("subject could not be classified"),
+            Self::UnknownType => f.write_str("unknown commit type"),
+            Self::MissingScope => f.write_str("missing scope"),
+            Self::UnknownScope(name) => write!(f, "unknown scope “{}”", name),
+            Self::DisallowedType(name) => write!(f, "type “{}” is not allowed", name),
+            Self::TooLong { length, max } => write!(
+                f,
+                "subject is {} characters long, at most {} are allowed",
+                length, max
+            ),
+        }
+    }
+}
+
+/// Requirements a commit message has to fulfill
+///
+/// Subjects which are not conventional commits, like merged pull requests or releases, are
+/// accepted as long as they are classified. The default policy only rejects subjects which
+/// would be classified as [`Subject::Simple`] or [`Type::Other`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Policy {
+    /// Conventional commits must have a scope
+    pub require_scope: bool,
+    /// Known scopes, empty allows any scope
+    pub scopes: Vec<String>,
+    /// Allowed conventional commit types, empty allows all known types
+    pub allowed_types: Vec<Type>,
+    /// Maximum subject length in characters
+    pub max_length: Option<usize>,
+}
+
+impl Policy {
+    /// Check a commit message with the default [`Classifier`]
+    ///
+    /// Returns an empty list if the message is acceptable.
+    #[must_use]
+    #[inline]
+    pub fn check(&self, message: &str) -> Vec<Violation> {
+        self.check_with(message, &DEFAULT)
+    }
+
+    /// Check a commit message with the given [`Classifier`]
+    #[must_use]
+    #[inline]
+    pub fn check_with(&self, message: &str, classifier: &Classifier) -> Vec<Violation> {
+        let text = message.lines().next().unwrap_or_default().trim_end();
+        if text.trim().is_empty() {
+            return vec![Violation::EmptySubject];
+        }
+        let mut result = vec![];
+        match classifier.classify_message(message).subject {
+            Subject::Simple { .. } => result.push(Violation::Unclassified),
+            Subject::ConventionalCommit {
+                category: Type::Other,
+                ..
+            } => result.push(Violation::UnknownType),
+            Subject::ConventionalCommit {
+                category, scope, ..
+            } => {
+                match scope {
+                    None if self.require_scope => result.push(Violation::MissingScope),
+                    Some(scope) if !self.scopes.is_empty() && !self.scopes.contains(&scope) => {
+                        result.push(Violation::UnknownScope(scope));
+                    }
+                    _ => {}
+                }
+                if !self.allowed_types.is_empty() && !self.allowed_types.contains(&category) {
+                    result.push(Violation::DisallowedType(category.as_str().to_owned()));
+                }
+            }
+            _ => {}
+        }
+        let length = text.chars().count();
+        if let Some(max) = self.max_length.filter(|max| length > *max) {
+            result.push(Violation::TooLong { length, max });
+        }
+        result
+    }
+}
+
+/// Remove what git removes from an edited commit message
+///
+/// Strips comment lines, everything below the scissors line and leading blank lines, like
+/// `git commit --cleanup=strip` does. Use it on the file passed to a `commit-msg` hook.
+///
+/// ```rust
+/// let message = "\nfix: Foo\n# Please enter the commit message\n\nBody\n";
+/// assert_eq!(subject_classifier::policy::strip_comments(message), "fix: Foo\n\nBody\n");
+/// ```
+#[must_use]
+#[inline]
+pub fn strip_comments(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    for line in message.lines() {
+        if line == SCISSORS {
+            break;
+        }
+        if line.starts_with('#') || (result.is_empty() && line.trim().is_empty()) {
+            continue;
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_comments, Policy, Violation};
+    use crate::{Classifier, Type};
+
+    #[test]
+    fn default_policy() {
+        let policy = Policy::default();
+        for text in [
+            "feat: Foo",
+            "Add Foo",
+            "Merge pull request #12 from foo/bar",
+            "Merge branch 'develop'",
+            "Release v1.0.0",
+            "fixup! feat: Foo",
+            "Revert \"feat: Foo\"",
+        ] {
+            assert!(policy.check(text).is_empty(), "{}", text);
+        }
+        assert_eq!(policy.check("Makefile: foo"), vec![Violation::UnknownType]);
+        assert_eq!(policy.check("[WIP] Foo"), vec![Violation::Unclassified]);
+        assert_eq!(policy.check(""), vec![Violation::EmptySubject]);
+        assert_eq!(policy.check("\n\nBody"), vec![Violation::EmptySubject]);
+    }
+
+    #[test]
+    fn scope_and_types() {
+        let policy = Policy {
+            require_scope: true,
+            allowed_types: vec![Type::Feat, Type::Fix],
+            ..Policy::default()
+        };
+        assert!(policy.check("feat(ui): Foo").is_empty());
+        assert_eq!(policy.check("feat: Foo"), vec![Violation::MissingScope]);
+        assert_eq!(
+            policy.check("chore(deps): Foo"),
+            vec![Violation::DisallowedType("chore".to_owned())]
+        );
+        // Not a conventional commit, scope & type requirements do not apply
+        assert!(policy.check("Release v1.0.0").is_empty());
+    }
+
+    #[test]
+    fn known_scopes() {
+        let policy = Policy {
+            scopes: vec!["ui".to_owned(), "api".to_owned()],
+            ..Policy::default()
+        };
+        assert!(policy.check("feat(api): Foo").is_empty());
+        assert!(policy.check("feat: Foo").is_empty());
+        assert_eq!(
+            policy.check("feat(db): Foo"),
+            vec![Violation::UnknownScope("db".to_owned())]
+        );
+    }
+
+    #[test]
+    fn custom_classifier() {
+        let classifier = Classifier::builder().category("ops").build();
+        let policy = Policy {
+            allowed_types: vec![Type::Custom("ops".to_owned())],
+            ..Policy::default()
+        };
+        assert!(policy.check_with("ops: Foo", &classifier).is_empty());
+        assert_eq!(
+            policy.check("ops: Foo"),
+            vec![Violation::UnknownType],
+            "unknown to the default classifier"
+        );
+    }
+
+    #[test]
+    fn max_length() {
+        let policy = Policy {
+            max_length: Some(10),
+            ..Policy::default()
+        };
+        assert!(policy.check("feat: Föö\n\nA long body line").is_empty());
+        assert_eq!(
+            policy.check("feat: Foo bar"),
+            vec![Violation::TooLong {
+                length: 13,
+                max: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn strip() {
+        let message = "feat: Foo\n\n# Comment\nBody\n# ------------------------ >8 ------------------------\ndiff --git a/foo b/foo\n";
+        assert_eq!(strip_comments(message), "feat: Foo\n\nBody\n");
+        assert_eq!(strip_comments("# Only comments\n"), "");
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Issue & ticket references mentioned in subjects.
+//!
+//! ```rust
+//! use subject_classifier::references::{Extractor, ReferenceKind};
+//! use subject_classifier::Subject;
+//!
+//! let subject = Subject::from("fix(api): Handle empty pages, fixes #9 & JIRA-1234");
+//! let references = subject.references();
+//! assert_eq!(references[0].kind, ReferenceKind::Issue);
+//! assert_eq!(references[0].id, "9");
+//! assert!(references[0].closes);
+//! assert_eq!(&subject.text()[references[1].span.clone()], "JIRA-1234");
+//!
+//! // Only accept the keys of the project
+//! let extractor = Extractor::new(["CSV-FOO"])?;
+//! let references = subject.references_with(&extractor);
+//! assert_eq!(references.len(), 1);
+//! # Ok::<(), regex::Error>(())
+//! ```
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::Span;
+
+/// Pattern matching the project key of a ticket reference like `JIRA-1234` or `CSV-FOO-1234`
+pub const DEFAULT_KEY: &str = "[A-Z][A-Z0-9]+(?:-[A-Z][A-Z0-9]+)*";
+
+/// Words matched by [`DEFAULT_KEY`] which are not project keys, e.g. in `UTF-8` or `SHA-256`
+const NON_TICKET_KEYS: &[&str] = &[
+    "AES", "CRC", "HTTP", "IEC", "IEEE", "ISO", "MD", "RFC", "RSA", "SHA", "SSL", "TLS", "UCS",
+    "UTF",
+];
+
+static DEFAULT: Lazy<Extractor> = Lazy::new(Extractor::default);
+
+/// The kind of a [`Reference`]
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ReferenceKind {
+    /// `#123`
+    Issue,
+    /// `GH-123`
+    GitHub,
+    /// `CVE-2022-1234`
+    Cve,
+    /// A project key followed by a number, e.g. `JIRA-1234`
+    Ticket,
+}
+
+/// An issue or ticket reference
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reference<'a> {
+    /// What is referenced
+    pub kind: ReferenceKind,
+    /// The issue number of [`ReferenceKind::Issue`] & [`ReferenceKind::GitHub`], the whole
+    /// reference otherwise
+    pub id: &'a str,
+    /// Byte range of the reference, without a closing keyword
+    pub span: Span,
+    /// Preceded by a closing keyword like `fixes`, `closes` or `resolves`
+    pub closes: bool,
+}
+
+/// Finds issue & ticket references in text
+///
+/// The default extractor accepts uppercase project keys with at least two letters, except for
+/// well-known words like `UTF` or `SHA`. Use [`Extractor::new`] with the keys of a project to
+/// only accept those.
+#[derive(Clone, Debug)]
+pub struct Extractor {
+    regex: Regex,
+    /// Reject keys which are unlikely to be project keys
+    heuristic: bool,
+}
+
+impl Default for Extractor {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            heuristic: true,
+            ..Self::new([DEFAULT_KEY]).expect("Valid Regex")
+        }
+    }
+}
+
+impl Extractor {
+    /// Create an extractor for tickets with the given project key patterns, e.g. `JIRA` or
+    /// `[A-Z]{2,}`
+    ///
+    /// Without keys only issue, GitHub & CVE references are found.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key is not a valid regular expression.
+    #[inline]
+    pub fn new<I, S>(keys: I) -> Result<Self, regex::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let keys: Vec<_> = keys
+            .into_iter()
+            .map(|key| format!("(?:{})", key.as_ref()))
+            .collect();
+        let ticket = if keys.is_empty() {
+            String::new()
+        } else {
+            format!("|(?P<ticket>(?:{})-\\d+)", keys.join("|"))
+        };
+        let regex = Regex::new(&format!(
+            r"(?:\b(?P<closes>(?i:fix(?:e[sd])?|close[sd]?|resolve[sd]?)):?\s+)?(?:(?P<cve>CVE-\d{{4}}-\d{{4,}})|GH-(?P<gh>\d+)|#(?P<issue>\d+){})\b",
+            ticket
+        ))?;
+        Ok(Self {
+            regex,
+            heuristic: false,
+        })
+    }
+
+    /// All references in `text`, in order of appearance
+    ///
+    /// Only the reference directly after a closing keyword is closed, i.e. in `fixes #1, #2`
+    /// the issue `#2` is just mentioned.
+    #[must_use]
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    // arithmetic: the prefix is part of the match before the id
+    pub fn extract<'a>(&self, text: &'a str) -> Vec<Reference<'a>> {
+        let mut result = vec![];
+        for caps in self.regex.captures_iter(text) {
+            // `\b` does not guard `#` and references must not continue a word like `C#1`
+            // or `CSV-FOO-1`
+            let start = caps.get(0).map_or(0, |m| m.start());
+            if text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+            {
+                continue;
+            }
+            let (kind, id) = if let Some(m) = caps.name("cve") {
+                (ReferenceKind::Cve, m)
+            } else if let Some(m) = caps.name("gh") {
+                (ReferenceKind::GitHub, m)
+            } else if let Some(m) = caps.name("issue") {
+                (ReferenceKind::Issue, m)
+            } else if let Some(m) = caps.name("ticket") {
+                if self.heuristic && !is_project_key(m.as_str()) {
+                    continue;
+                }
+                (ReferenceKind::Ticket, m)
+            } else {
+                continue;
+            };
+            let prefix = match kind {
+                ReferenceKind::GitHub => "GH-".len(),
+                ReferenceKind::Issue => "#".len(),
+                ReferenceKind::Cve | ReferenceKind::Ticket => 0,
+            };
+            let closes = caps.name("closes").is_some();
+            result.push(Reference {
+                kind,
+                id: id.as_str(),
+                span: id.start() - prefix..id.end(),
+                closes,
+            });
+        }
+        result
+    }
+}
+
+/// Returns `false` for tickets like `UTF-8` or `X86-64`
+fn is_project_key(ticket: &str) -> bool {
+    let key = ticket.rsplit_once('-').map_or(ticket, |(key, _)| key);
+    key.chars().filter(char::is_ascii_alphabetic).count() >= 2 && !NON_TICKET_KEYS.contains(&key)
+}
+
+/// All references in `text` using the default [`Extractor`]
+#[must_use]
+#[inline]
+pub fn extract(text: &str) -> Vec<Reference<'_>> {
+    DEFAULT.extract(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract, Extractor, Reference, ReferenceKind};
+    use crate::Subject;
+
+    fn ids(references: &[Reference<'_>]) -> Vec<(ReferenceKind, String)> {
+        references
+            .iter()
+            .map(|r| (r.kind, r.id.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn kinds() {
+        let text = "Fix CVE-2022-1234 (GH-42, #123) in JIRA-1234";
+        assert_eq!(
+            extract(text),
+            vec![
+                Reference {
+                    kind: ReferenceKind::Cve,
+                    id: "CVE-2022-1234",
+                    span: 4..17,
+                    closes: true,
+                },
+                Reference {
+                    kind: ReferenceKind::GitHub,
+                    id: "42",
+                    span: 19..24,
+                    closes: false,
+                },
+                Reference {
+                    kind: ReferenceKind::Issue,
+                    id: "123",
+                    span: 26..30,
+                    closes: false,
+                },
+                Reference {
+                    kind: ReferenceKind::Ticket,
+                    id: "JIRA-1234",
+                    span: 35..44,
+                    closes: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn closing_keywords() {
+        for text in [
+            "fix #9",
+            "Fixes #9",
+            "fixed: #9",
+            "close #9",
+            "Closes #9",
+            "closed  #9",
+            "resolve #9",
+            "RESOLVES #9",
+            "resolved #9",
+        ] {
+            let references = extract(text);
+            assert_eq!(references.len(), 1, "{}", text);
+            assert!(references[0].closes, "{}", text);
+            assert_eq!(&text[references[0].span.clone()], "#9", "{}", text);
+        }
+        let references = extract("prefixes #9, fixes #1, #2");
+        assert_eq!(
+            references.iter().map(|r| r.closes).collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+    }
+
+    #[test]
+    fn no_references() {
+        for text in [
+            "Support C#9",
+            "Bump foo#12",
+            "Use UTF-8 & SHA-256",
+            "Build for X86-64",
+            "Use GH-2a",
+            "Rename ABC_DEF-1",
+            "#",
+            "",
+        ] {
+            assert_eq!(extract(text), vec![], "{}", text);
+        }
+    }
+
+    #[test]
+    fn project_keys() {
+        let text = "Fix CSV-FOO-1234 & UTF-8 decoding in AB-12, see #7";
+        assert_eq!(
+            ids(&extract(text)),
+            vec![
+                (ReferenceKind::Ticket, "CSV-FOO-1234".to_owned()),
+                (ReferenceKind::Ticket, "AB-12".to_owned()),
+                (ReferenceKind::Issue, "7".to_owned()),
+            ]
+        );
+        let extractor = Extractor::new(["CSV-FOO", "JIRA", "UTF"]).expect("Valid keys");
+        assert_eq!(
+            ids(&extractor.extract(text)),
+            vec![
+                (ReferenceKind::Ticket, "CSV-FOO-1234".to_owned()),
+                (ReferenceKind::Ticket, "UTF-8".to_owned()),
+                (ReferenceKind::Issue, "7".to_owned()),
+            ]
+        );
+        let extractor = Extractor::new::<_, &str>([]).expect("Valid keys");
+        assert_eq!(
+            ids(&extractor.extract(text)),
+            vec![(ReferenceKind::Issue, "7".to_owned())]
+        );
+        assert!(Extractor::new(["JIRA("]).is_err());
+    }
+
+    #[test]
+    fn subject() {
+        let subject = Subject::from("security: Fix CSV-FOO-1234, closes GH-3 (#4512)");
+        let references = subject.references();
+        assert_eq!(
+            ids(&references),
+            vec![
+                (ReferenceKind::Ticket, "CSV-FOO-1234".to_owned()),
+                (ReferenceKind::GitHub, "3".to_owned()),
+                (ReferenceKind::Issue, "4512".to_owned()),
+            ]
+        );
+        assert!(references[0].closes);
+        assert!(references[1].closes);
+        assert_eq!(&subject.text()[references[2].span.clone()], "#4512");
+    }
+}
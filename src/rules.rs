@@ -0,0 +1,835 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Recognizers used by a [`Classifier`] to classify a subject.
+//!
+//! A [`Classifier`] tries its rules in order, the first rule returning a [`SubjectRef`] wins. If no
+//! rule applies the subject is a [`Subject::Simple`](crate::Subject::Simple).
+//!
+//! ```rust
+//! use subject_classifier::rules::{self, Rule};
+//! use subject_classifier::{Classifier, Forge, Subject, SubjectKind, SubjectRef};
+//!
+//! #[derive(Debug)]
+//! struct MergeBot;
+//!
+//! impl Rule for MergeBot {
+//!     fn name(&self) -> &'static str {
+//!         "merge-bot"
+//!     }
+//!
+//!     fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+//!         subject.strip_prefix("[merge-bot] !")?;
+//!         let kind = SubjectKind::PullRequest {
+//!             forge: Forge::Other,
+//!             id: Some(13..subject.len()),
+//!             description: 0..subject.len(),
+//!             source: None,
+//!             target: None,
+//!             remote: None,
+//!             repository: None,
+//!         };
+//!         Some(SubjectRef::new(subject, kind))
+//!     }
+//! }
+//!
+//! let classifier = Classifier::builder()
+//!     .insert_rule(0, MergeBot)
+//!     .remove_rule(rules::Revert.name())
+//!     .build();
+//! assert!(matches!(
+//!     classifier.classify("[merge-bot] !42"),
+//!     Subject::PullRequest { .. }
+//! ));
+//! assert!(matches!(
+//!     classifier.classify("Revert foo"),
+//!     Subject::ConventionalCommit { .. }
+//! ));
+//! ```
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::subject_ref::Span;
+
+use crate::{Classifier, Forge, SubjectKind, SubjectRef, SubtreeOperationKind, Type};
+
+regex!(
+    CONVENTIONAL_COMMIT_REGEX,
+    r"(?i)^(SECURITY FIX!?|BREAKING CHANGE!?|\w+!?)(\(.+\)!?)?[/:\s]*(.+)"
+);
+
+regex!(ADD_REGEX, r"(?i)^add:?\s*");
+regex!(FIX_REGEX, r"(?i)^(bug)?fix(ing|ed)?(\(.+\))?[/:\s]+");
+regex!(TYPE_PREFIX_REGEX, r"^(\w+)(?:\([^)]*\))?!?:");
+
+regex!(UPDATE_REGEX, r#"^Update :?(.+) to (.+)"#);
+regex!(SPLIT_REGEX, r#"^Split '(.+)/' into commit '(.+)'"#);
+regex!(IMPORT_REGEX, r#"^:?(.+) Import .+⸪(.+)"#);
+
+regex!(
+    PR_REGEX,
+    r"^Merge (?:remote-tracking branch '(.+)/(pr/(\d+))'|pull request #(\d+) from (.+))$"
+);
+// https://github.com/apps/bors
+regex!(PR_REGEX_BORS, r"^Merge #(\d+)");
+regex!(
+    PR_REGEX_BB,
+    r"^Merge pull request #(\d+) in (\S+) from (.+?)(?: to (.+))?$"
+);
+regex!(PR_REGEX_AZURE, r"^Merged PR (\d+): (.*)$");
+regex!(PR_REGEX_GITLAB, r"^Merge branch '(.+)' into '(.+)'$");
+regex!(PR_REGEX_GERRIT, r#"^Merge "(.+)"(?: into (.+))?$"#);
+
+regex!(
+    MERGE_REGEX,
+    r"^Merge (remote-tracking )?branch(?:es)? ('[^']+'(?:(?:,|,? and) '[^']+')*)(?: of (\S+))?(?: into '?([^'\s]+)'?)?$"
+);
+regex!(BRANCH_REGEX, r"'([^']+)'");
+
+static RELEASE_REGEX1: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"^(?:Release|Bump) :?(.+)@v?([0-9.]+)\b.*"#)
+        .case_insensitive(true)
+        .build()
+        .expect("Valid Regex")
+});
+
+static RELEASE_REGEX2: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"^(?:Release|Bump)\s.*?v?([0-9.]+).*"#)
+        .case_insensitive(true)
+        .build()
+        .expect("Valid Regex")
+});
+
+static DEPENDENCY_BUMP_REGEX: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"^Bump (\S+) from \S+ to \S+"#)
+        .case_insensitive(true)
+        .build()
+        .expect("Valid Regex")
+});
+
+/// How a rule arrived at its classification, ordered from least to most confident
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Derivation {
+    /// Nothing specific was recognized, e.g. a conventional commit with an unknown type
+    Fallback,
+    /// Guessed from a keyword, e.g. `Add a button` is a [`Type::Feat`]
+    Keyword,
+    /// A merge commit pattern of git, a forge or a merge bot
+    Merge,
+    /// Explicitly stated, e.g. a conventional commit prefix like `feat:`, `fixup!` or a subject
+    /// generated by `git-subtree`
+    Explicit,
+}
+
+impl Derivation {
+    /// Confidence in a classification derived this way, between `0.0` and `1.0`
+    #[must_use]
+    #[inline]
+    pub const fn confidence(self) -> f32 {
+        match self {
+            Self::Fallback => 0.2,
+            Self::Keyword => 0.6,
+            Self::Merge => 0.9,
+            Self::Explicit => 1.0,
+        }
+    }
+}
+
+/// A recognizer for a particular subject format
+pub trait Rule: Debug + Send + Sync {
+    /// Unique name of the rule, used to remove it from a [`Classifier`]
+    fn name(&self) -> &'static str;
+
+    /// Returns the classified subject or `None` if the rule does not apply
+    ///
+    /// The `classifier` provides access to the configured type table.
+    fn classify<'a>(&self, subject: &'a str, classifier: &Classifier) -> Option<SubjectRef<'a>>;
+
+    /// A regular expression matching every subject the rule may apply to
+    ///
+    /// The [`Classifier`] matches the patterns of all rules in a single pass and skips the
+    /// rules whose pattern does not match. Rules without a pattern are always tried.
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        None
+    }
+
+    /// How the rule derived a result of [`Rule::classify`], [`Derivation::Keyword`] unless
+    /// overridden
+    #[inline]
+    fn derivation(&self, _result: &SubjectRef<'_>) -> Derivation {
+        Derivation::Keyword
+    }
+}
+
+/// The built-in rules in the order used by the default [`Classifier`]
+#[must_use]
+#[inline]
+pub fn defaults() -> Vec<Arc<dyn Rule>> {
+    vec![
+        Arc::new(Release),
+        Arc::new(AzurePullRequest),
+        Arc::new(GitHubPullRequest),
+        Arc::new(BitbucketPullRequest),
+        Arc::new(BorsPullRequest),
+        Arc::new(GitLabMergeRequest),
+        Arc::new(GerritChange),
+        Arc::new(Merge),
+        Arc::new(Fixup),
+        Arc::new(Subtree),
+        Arc::new(Remove),
+        Arc::new(Rename),
+        Arc::new(Revert),
+        Arc::new(Add),
+        Arc::new(Fix),
+        Arc::new(Deprecate),
+        Arc::new(ConventionalCommit),
+    ]
+}
+
+/// Commits releasing something, e.g. `Release foo@v2.11.0` or `Bump v2.11.0`
+///
+/// Dependency updates like `Bump regex from 1.5.4 to 1.5.5` are not releases.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Release;
+
+impl Rule for Release {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "release"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        if let Some(caps) = DEPENDENCY_BUMP_REGEX.captures(subject) {
+            if !caps[1].eq_ignore_ascii_case("version") {
+                return None;
+            }
+        }
+        let kind = if let Some(caps) = RELEASE_REGEX1.captures(subject) {
+            SubjectKind::Release {
+                version: span(&caps, 2),
+                scope: Some(span(&caps, 1)),
+            }
+        } else {
+            let caps = RELEASE_REGEX2.captures(subject)?;
+            SubjectKind::Release {
+                version: span(&caps, 1),
+                scope: None,
+            }
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"(?i)^(?:Release|Bump)\s")
+    }
+}
+
+/// Azure DevOps merges, e.g. `Merged PR 36587: Add Foo`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AzurePullRequest;
+
+impl Rule for AzurePullRequest {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-azure"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX_AZURE.captures(subject)?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::Azure,
+            id: Some(span(&caps, 1)),
+            description: span(&caps, 2),
+            source: None,
+            target: None,
+            remote: None,
+            repository: None,
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merged PR \d")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// GitHub merges, e.g. `Merge pull request #126 from foo/bar` or a pull request fetched to a
+/// remote-tracking branch like `Merge remote-tracking branch 'origin/pr/126'`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GitHubPullRequest;
+
+impl Rule for GitHubPullRequest {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-github"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX.captures(subject)?;
+        let id = caps.get(3).or_else(|| caps.get(4))?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::GitHub,
+            id: Some(id.range()),
+            description: 0..subject.len(),
+            source: caps.get(2).or_else(|| caps.get(5)).map(|m| m.range()),
+            target: None,
+            remote: caps.get(1).map(|m| m.range()),
+            repository: None,
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merge (?:remote-tracking branch|pull request #)")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// Bitbucket merges, e.g. `Merge pull request #7771 in FOO/bar from feature/x to development`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BitbucketPullRequest;
+
+impl Rule for BitbucketPullRequest {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-bitbucket"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX_BB.captures(subject)?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::Bitbucket,
+            id: Some(span(&caps, 1)),
+            description: 0..subject.len(),
+            source: Some(span(&caps, 3)),
+            target: caps.get(4).map(|m| m.range()),
+            remote: None,
+            repository: Some(span(&caps, 2)),
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merge pull request #\d+ in ")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// Merges done by the [Bors GitHub App](https://github.com/apps/bors), e.g. `Merge #123`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BorsPullRequest;
+
+impl Rule for BorsPullRequest {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-bors"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX_BORS.captures(subject)?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::Bors,
+            id: Some(span(&caps, 1)),
+            description: 0..subject.len(),
+            source: None,
+            target: None,
+            remote: None,
+            repository: None,
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merge #\d")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// GitLab merges, e.g. `Merge branch 'feature/x' into 'main'`
+///
+/// The merge request id & project are only part of the message body, see
+/// [`CommitMessage`](crate::CommitMessage).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GitLabMergeRequest;
+
+impl Rule for GitLabMergeRequest {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-gitlab"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX_GITLAB.captures(subject)?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::GitLab,
+            id: None,
+            description: 0..subject.len(),
+            source: Some(span(&caps, 1)),
+            target: Some(span(&caps, 2)),
+            remote: None,
+            repository: None,
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merge branch '.+' into '")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// Changes submitted by Gerrit, e.g. `Merge "Add Foo" into stable`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GerritChange;
+
+impl Rule for GerritChange {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "pr-gerrit"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let caps = PR_REGEX_GERRIT.captures(subject)?;
+        let kind = SubjectKind::PullRequest {
+            forge: Forge::Gerrit,
+            id: None,
+            description: span(&caps, 1),
+            source: None,
+            target: caps.get(2).map(|m| m.range()),
+            remote: None,
+            repository: None,
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r#"^Merge ""#)
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// Merges created by `git merge` & `git pull`, e.g. `Merge branch 'develop' into main`,
+/// `Merge remote-tracking branch 'origin/main'` or `Merge branches 'a', 'b' and 'c'`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Merge;
+
+impl Rule for Merge {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "merge"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        MERGE_REGEX
+            .captures(subject)
+            .and_then(|caps| parse_merge(&caps, subject))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^Merge (?:remote-tracking )?branch")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Merge
+    }
+}
+
+/// Commits created by `git commit --fixup`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fixup;
+
+impl Rule for Fixup {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "fixup"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        subject
+            .starts_with("fixup!")
+            .then(|| SubjectRef::new(subject, SubjectKind::Fixup))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^fixup!")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Explicit
+    }
+}
+
+/// Commits created by `git-stree`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Subtree;
+
+impl Rule for Subtree {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "subtree"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        let (operation, caps) = if let Some(caps) = UPDATE_REGEX.captures(subject) {
+            (SubtreeOperationKind::Update, caps)
+        } else if let Some(caps) = IMPORT_REGEX.captures(subject) {
+            (SubtreeOperationKind::Import, caps)
+        } else {
+            (SubtreeOperationKind::Split, SPLIT_REGEX.captures(subject)?)
+        };
+        let kind = SubjectKind::SubtreeCommit {
+            operation,
+            subtree: span(&caps, 1),
+            git_ref: span(&caps, 2),
+        };
+        Some(SubjectRef::new(subject, kind))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"^(?:Update |Split ')| Import ")
+    }
+
+    #[inline]
+    fn derivation(&self, _: &SubjectRef<'_>) -> Derivation {
+        Derivation::Explicit
+    }
+}
+
+/// Subjects starting with the word “remove”
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Remove;
+
+impl Rule for Remove {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "remove"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        starts_with_word(subject, "remove ").then(|| SubjectRef::new(subject, SubjectKind::Remove))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"(?i)^remove ")
+    }
+}
+
+/// Subjects starting with the word “rename” or “move”
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rename;
+
+impl Rule for Rename {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "rename"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        (starts_with_word(subject, "rename ") || starts_with_word(subject, "move "))
+            .then(|| SubjectRef::new(subject, SubjectKind::Rename))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"(?i)^(?:rename|move) ")
+    }
+}
+
+/// Commits created by `git-revert`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Revert;
+
+impl Rule for Revert {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "revert"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        starts_with_word(subject, "revert ").then(|| SubjectRef::new(subject, SubjectKind::Revert))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"(?i)^revert ")
+    }
+}
+
+/// Subjects starting with the word “add” are a [`Type::Feat`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Add;
+
+impl Rule for Add {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "add"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, classifier: &Classifier) -> Option<SubjectRef<'a>> {
+        (ADD_REGEX.is_match(subject) && !has_explicit_type(subject, classifier))
+            .then(|| heuristic(Type::Feat, subject))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(ADD_REGEX.as_str())
+    }
+}
+
+/// Subjects starting with the word “fix”, “bugfix”, “fixed”… are a [`Type::Fix`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fix;
+
+impl Rule for Fix {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "fix"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, classifier: &Classifier) -> Option<SubjectRef<'a>> {
+        (FIX_REGEX.is_match(subject) && !has_explicit_type(subject, classifier))
+            .then(|| heuristic(Type::Fix, subject))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(FIX_REGEX.as_str())
+    }
+}
+
+/// Subjects starting with the word “deprecate” are a [`Type::Deprecate`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Deprecate;
+
+impl Rule for Deprecate {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "deprecate"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, _: &Classifier) -> Option<SubjectRef<'a>> {
+        starts_with_word(subject, "deprecate ").then(|| heuristic(Type::Deprecate, subject))
+    }
+
+    #[inline]
+    fn pattern(&self) -> Option<&str> {
+        Some(r"(?i)^deprecate ")
+    }
+}
+
+/// Conventional commits like `feat(ui)!: Add a button`
+///
+/// Leniently accepts anything starting with a word, unknown types become [`Type::Other`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConventionalCommit;
+
+impl Rule for ConventionalCommit {
+    #[inline]
+    fn name(&self) -> &'static str {
+        "conventional-commit"
+    }
+
+    #[inline]
+    fn classify<'a>(&self, subject: &'a str, classifier: &Classifier) -> Option<SubjectRef<'a>> {
+        CONVENTIONAL_COMMIT_REGEX
+            .captures(subject)
+            .map(|caps| parse_conventional_commit(&caps, subject, classifier))
+    }
+
+    #[inline]
+    fn derivation(&self, result: &SubjectRef<'_>) -> Derivation {
+        if result.category() == Some(&Type::Other) {
+            Derivation::Fallback
+        } else {
+            Derivation::Explicit
+        }
+    }
+}
+
+/// Span of the capture group `i`, empty if the group did not participate in the match
+fn span(caps: &Captures<'_>, i: usize) -> Span {
+    caps.get(i).map_or(0..0, |m| m.range())
+}
+
+/// Returns `true` if `subject` starts with a known type followed by a colon, like `fix(ui): `
+///
+/// Keyword heuristics leave those to [`ConventionalCommit`].
+fn has_explicit_type(subject: &str, classifier: &Classifier) -> bool {
+    type_prefix(subject).is_some_and(|word| classifier.type_of(word) != Type::Other)
+}
+
+/// The type word of a subject starting with `type(scope)!:`, known or not
+pub(crate) fn type_prefix(subject: &str) -> Option<&str> {
+    TYPE_PREFIX_REGEX
+        .captures(subject)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+/// Case-insensitive prefix check, `word` must be lowercase ASCII
+fn starts_with_word(subject: &str, word: &str) -> bool {
+    subject
+        .get(..word.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(word))
+}
+
+fn heuristic(category: Type, subject: &str) -> SubjectRef<'_> {
+    let kind = SubjectKind::ConventionalCommit {
+        category,
+        type_span: None,
+        scope: None,
+        breaking: None,
+        description: 0..subject.len(),
+    };
+    SubjectRef::new(subject, kind)
+}
+
+#[allow(clippy::arithmetic_side_effects)]
+fn parse_merge<'a>(caps: &Captures<'_>, subject: &'a str) -> Option<SubjectRef<'a>> {
+    // arithmetic: offsets of matches inside `subject`, `/` is one byte
+    let list = caps.get(2)?;
+    let mut branches: Vec<Span> = BRANCH_REGEX
+        .captures_iter(list.as_str())
+        .map(|c| span(&c, 1))
+        .map(|b| list.start() + b.start..list.start() + b.end)
+        .collect();
+    let mut remote = caps.get(3).map(|m| m.range());
+    if caps.get(1).is_some() {
+        // remote-tracking branches are prefixed by their remote
+        let first = branches.first()?.clone();
+        let name = subject[first.clone()].split_once('/')?.0;
+        remote = Some(first.start..first.start + name.len());
+        for branch in &mut branches {
+            if subject[branch.clone()]
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.starts_with('/'))
+            {
+                branch.start += name.len() + 1;
+            }
+        }
+    }
+    let kind = SubjectKind::Merge {
+        branches,
+        remote,
+        target: caps.get(4).map(|m| m.range()),
+    };
+    Some(SubjectRef::new(subject, kind))
+}
+
+#[allow(clippy::arithmetic_side_effects)]
+fn parse_conventional_commit<'a>(
+    caps: &Captures<'_>,
+    subject: &'a str,
+    classifier: &Classifier,
+) -> SubjectRef<'a> {
+    // arithmetic: the conditions guard the arithmetic, `!` & parentheses are one byte
+    let mut type_span = span(caps, 1);
+    let mut scope_span = span(caps, 2);
+    let mut breaking = None;
+
+    if subject[type_span.clone()].ends_with('!') {
+        type_span.end -= 1;
+        breaking = Some(type_span.end..type_span.end + 1);
+    }
+    if subject[scope_span.clone()].ends_with('!') {
+        scope_span.end -= 1;
+        breaking = Some(scope_span.end..scope_span.end + 1);
+    }
+    if scope_span.len() >= 3 {
+        scope_span = scope_span.start + 1..scope_span.end - 1;
+    }
+    let type_text = &subject[type_span.clone()];
+    if breaking.is_none() && type_text.eq_ignore_ascii_case("breaking change") {
+        breaking = Some(type_span.clone());
+    }
+
+    let scope = (!scope_span.is_empty()).then_some(scope_span);
+    let category = classifier.type_of(type_text);
+    let description = if category == Type::Other {
+        span(caps, 0)
+    } else {
+        span(caps, 3)
+    };
+
+    let kind = SubjectKind::ConventionalCommit {
+        category,
+        type_span: Some(type_span),
+        scope,
+        breaking,
+        description,
+    };
+    SubjectRef::new(subject, kind)
+}
@@ -0,0 +1,585 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Stable [serde](https://serde.rs) representation of the classification results
+//!
+//! The representation is decoupled from the Rust types, so that it stays stable when the
+//! enums change.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Forge, Subject, SubtreeOperation, Type};
+
+impl Serialize for Type {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Type {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from_name(&name))
+    }
+}
+
+/// Serialized form of a [`Subject`]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum SubjectRepr {
+    Conventional {
+        #[serde(rename = "type")]
+        category: Type,
+        scope: Option<String>,
+        breaking: bool,
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Fixup {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Merge {
+        branches: Vec<String>,
+        remote: Option<String>,
+        target: Option<String>,
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    PullRequest {
+        id: Option<String>,
+        description: String,
+        text: String,
+        forge: Forge,
+        source: Option<String>,
+        target: Option<String>,
+        remote: Option<String>,
+        repository: Option<String>,
+    },
+    Release {
+        version: String,
+        scope: Option<String>,
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Remove {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Rename {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Revert {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Subtree {
+        #[serde(flatten)]
+        operation: SubtreeOperation,
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+    Simple {
+        description: String,
+        text: String,
+        pull_request: Option<String>,
+    },
+}
+
+impl From<Subject> for SubjectRepr {
+    #[inline]
+    fn from(subject: Subject) -> Self {
+        match subject {
+            Subject::ConventionalCommit {
+                breaking_change,
+                category,
+                scope,
+                description,
+                text,
+                pull_request,
+            } => Self::Conventional {
+                category,
+                scope,
+                breaking: breaking_change,
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Fixup {
+                description,
+                text,
+                pull_request,
+            } => Self::Fixup {
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Merge {
+                branches,
+                remote,
+                target,
+                description,
+                text,
+                pull_request,
+            } => Self::Merge {
+                branches,
+                remote,
+                target,
+                description,
+                text,
+                pull_request,
+            },
+            Subject::PullRequest {
+                id,
+                description,
+                text,
+                forge,
+                source,
+                target,
+                remote,
+                repository,
+            } => Self::PullRequest {
+                id,
+                description,
+                text,
+                forge,
+                source,
+                target,
+                remote,
+                repository,
+            },
+            Subject::Release {
+                version,
+                scope,
+                description,
+                text,
+                pull_request,
+            } => Self::Release {
+                version,
+                scope,
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Remove {
+                description,
+                text,
+                pull_request,
+            } => Self::Remove {
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Rename {
+                description,
+                text,
+                pull_request,
+            } => Self::Rename {
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Revert {
+                description,
+                text,
+                pull_request,
+            } => Self::Revert {
+                description,
+                text,
+                pull_request,
+            },
+            Subject::SubtreeCommit {
+                operation,
+                description,
+                text,
+                pull_request,
+            } => Self::Subtree {
+                operation,
+                description,
+                text,
+                pull_request,
+            },
+            Subject::Simple {
+                description,
+                text,
+                pull_request,
+            } => Self::Simple {
+                description,
+                text,
+                pull_request,
+            },
+        }
+    }
+}
+
+impl From<SubjectRepr> for Subject {
+    #[inline]
+    fn from(repr: SubjectRepr) -> Self {
+        match repr {
+            SubjectRepr::Conventional {
+                category,
+                scope,
+                breaking,
+                description,
+                text,
+                pull_request,
+            } => Self::ConventionalCommit {
+                breaking_change: breaking,
+                category,
+                scope,
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Fixup {
+                description,
+                text,
+                pull_request,
+            } => Self::Fixup {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Merge {
+                branches,
+                remote,
+                target,
+                description,
+                text,
+                pull_request,
+            } => Self::Merge {
+                branches,
+                remote,
+                target,
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::PullRequest {
+                id,
+                description,
+                text,
+                forge,
+                source,
+                target,
+                remote,
+                repository,
+            } => Self::PullRequest {
+                id,
+                description,
+                text,
+                forge,
+                source,
+                target,
+                remote,
+                repository,
+            },
+            SubjectRepr::Release {
+                version,
+                scope,
+                description,
+                text,
+                pull_request,
+            } => Self::Release {
+                version,
+                scope,
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Remove {
+                description,
+                text,
+                pull_request,
+            } => Self::Remove {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Rename {
+                description,
+                text,
+                pull_request,
+            } => Self::Rename {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Revert {
+                description,
+                text,
+                pull_request,
+            } => Self::Revert {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Subtree {
+                operation,
+                description,
+                text,
+                pull_request,
+            } => Self::SubtreeCommit {
+                operation,
+                description,
+                text,
+                pull_request,
+            },
+            SubjectRepr::Simple {
+                description,
+                text,
+                pull_request,
+            } => Self::Simple {
+                description,
+                text,
+                pull_request,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{Subject, Type};
+
+    fn round_trip(text: &str, expected: &serde_json::Value) {
+        let subject = Subject::from(text);
+        let value = serde_json::to_value(&subject).expect("Serializable");
+        assert_eq!(&value, expected, "{}", text);
+        let result: Subject = serde_json::from_value(value).expect("Deserializable");
+        assert_eq!(result, subject);
+    }
+
+    #[test]
+    fn conventional_commit() {
+        round_trip(
+            "feat(ui)!: Foo",
+            &json!({
+                "kind": "conventional",
+                "type": "feat",
+                "scope": "ui",
+                "breaking": true,
+                "description": "Foo",
+                "text": "feat(ui)!: Foo",
+                "pull_request": null
+            }),
+        );
+        round_trip(
+            "Makefile: replace '-' in plugins_var",
+            &json!({
+                "kind": "conventional",
+                "type": "other",
+                "scope": null,
+                "breaking": false,
+                "description": "Makefile: replace '-' in plugins_var",
+                "text": "Makefile: replace '-' in plugins_var",
+                "pull_request": null
+            }),
+        );
+    }
+
+    #[test]
+    fn custom_type() {
+        let subject = Subject::ConventionalCommit {
+            breaking_change: false,
+            category: Type::Custom("ops".to_owned()),
+            scope: None,
+            description: "Foo".to_owned(),
+            text: "ops: Foo".to_owned(),
+            pull_request: None,
+        };
+        let text = serde_json::to_string(&subject).expect("Serializable");
+        assert_eq!(
+            text,
+            r#"{"kind":"conventional","type":"ops","scope":null,"breaking":false,"description":"Foo","text":"ops: Foo","pull_request":null}"#
+        );
+        let result: Subject = serde_json::from_str(&text).expect("Deserializable");
+        assert_eq!(result, subject);
+    }
+
+    #[test]
+    fn types() {
+        for name in [
+            "archive",
+            "build",
+            "change",
+            "chore",
+            "ci",
+            "dev",
+            "deps",
+            "docs",
+            "deprecate",
+            "feat",
+            "fix",
+            "i18n",
+            "issue",
+            "improvement",
+            "other",
+            "perf",
+            "refactor",
+            "repo",
+            "security",
+            "style",
+            "test",
+        ] {
+            let category: Type = serde_json::from_value(json!(name)).expect("Deserializable");
+            assert!(!matches!(category, Type::Custom(_)), "{}", name);
+            assert_eq!(
+                serde_json::to_value(&category).expect("Serializable"),
+                json!(name)
+            );
+        }
+    }
+
+    #[test]
+    fn fixup() {
+        round_trip(
+            "fixup! Foo",
+            &json!({"kind": "fixup", "description": "fixup! Foo", "text": "fixup! Foo", "pull_request": null}),
+        );
+    }
+
+    #[test]
+    fn pull_request() {
+        round_trip(
+            "Merged PR 36587: Add Foo",
+            &json!({
+                "kind": "pull_request",
+                "id": "36587",
+                "description": "Add Foo",
+                "text": "Merged PR 36587: Add Foo",
+                "forge": "azure",
+                "source": null,
+                "target": null,
+                "remote": null,
+                "repository": null
+            }),
+        );
+    }
+
+    #[test]
+    fn merge() {
+        round_trip(
+            "Merge remote-tracking branch 'origin/main' into dev",
+            &json!({
+                "kind": "merge",
+                "branches": ["main"],
+                "remote": "origin",
+                "target": "dev",
+                "description": "Merge remote-tracking branch 'origin/main' into dev",
+                "text": "Merge remote-tracking branch 'origin/main' into dev",
+                "pull_request": null
+            }),
+        );
+    }
+
+    #[test]
+    fn release() {
+        round_trip(
+            "Release foo@v2.11.0",
+            &json!({
+                "kind": "release",
+                "version": "2.11.0",
+                "scope": "foo",
+                "description": "Release foo@v2.11.0",
+                "text": "Release foo@v2.11.0",
+                "pull_request": null
+            }),
+        );
+    }
+
+    #[test]
+    fn remove_rename_revert() {
+        round_trip(
+            "Remove Foo",
+            &json!({"kind": "remove", "description": "Remove Foo", "text": "Remove Foo", "pull_request": null}),
+        );
+        round_trip(
+            "Remove Foo (#12)",
+            &json!({"kind": "remove", "description": "Remove Foo", "text": "Remove Foo (#12)", "pull_request": "12"}),
+        );
+        round_trip(
+            "Rename Foo",
+            &json!({"kind": "rename", "description": "Rename Foo", "text": "Rename Foo", "pull_request": null}),
+        );
+        round_trip(
+            "Revert Foo",
+            &json!({"kind": "revert", "description": "Revert Foo", "text": "Revert Foo", "pull_request": null}),
+        );
+    }
+
+    #[test]
+    fn subtree() {
+        round_trip(
+            "Update :qubes-builder to 5e5301b8eac",
+            &json!({
+                "kind": "subtree",
+                "operation": "update",
+                "subtree": "qubes-builder",
+                "git_ref": "5e5301b8eac",
+                "description": "Update :qubes-builder to 5e5301b8eac",
+                "text": "Update :qubes-builder to 5e5301b8eac",
+                "pull_request": null
+            }),
+        );
+        round_trip(
+            "Split 'rust/' into commit 'baa7766'",
+            &json!({
+                "kind": "subtree",
+                "operation": "split",
+                "subtree": "rust",
+                "git_ref": "baa7766",
+                "description": "Split 'rust/' into commit 'baa7766'",
+                "text": "Split 'rust/' into commit 'baa7766'",
+                "pull_request": null
+            }),
+        );
+        round_trip(
+            ":php/foo Import GH:github.com/foo⸪master",
+            &json!({
+                "kind": "subtree",
+                "operation": "import",
+                "subtree": "php/foo",
+                "git_ref": "master",
+                "description": ":php/foo Import GH:github.com/foo⸪master",
+                "text": ":php/foo Import GH:github.com/foo⸪master",
+                "pull_request": null
+            }),
+        );
+    }
+
+    #[test]
+    fn simple() {
+        round_trip(
+            "",
+            &json!({"kind": "simple", "description": "", "text": "", "pull_request": null}),
+        );
+    }
+}
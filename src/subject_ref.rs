@@ -0,0 +1,488 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Zero-copy classification results
+
+use std::ops::Range;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::classifier::DEFAULT;
+use crate::{Forge, Subject, SubtreeOperation, Type};
+
+/// A byte range into the classified text
+pub type Span = Range<usize>;
+
+regex!(SQUASH_REGEX, r" \(#(\d+)\)$");
+
+/// Split off the pull request reference GitHub appends to squash merges, e.g. ` (#123)`
+///
+/// Returns the text before the reference and the span of the pull request id.
+pub(crate) fn split_squash(text: &str) -> (&str, Option<Span>) {
+    SQUASH_REGEX
+        .captures(text)
+        .and_then(|caps| Some((caps.get(0)?, caps.get(1)?)))
+        .map_or((text, None), |(all, id)| {
+            (&text[..all.start()], Some(id.range()))
+        })
+}
+
+/// The kind of subtree operation, see [`SubtreeOperation`]
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SubtreeOperationKind {
+    Import,
+    Split,
+    Update,
+}
+
+/// The classification of a [`SubjectRef`], all strings are stored as [`Span`]s
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubjectKind {
+    /// Conventional Commit following the specification
+    ConventionalCommit {
+        /// The resolved type
+        category: Type,
+        /// The type as written, `None` if the type was guessed from a keyword
+        type_span: Option<Span>,
+        /// The scope without parentheses
+        scope: Option<Span>,
+        /// The `!` marker or the `BREAKING CHANGE` type
+        breaking: Option<Span>,
+        /// Text after the type & scope
+        description: Span,
+    },
+    /// Git fixup commit
+    Fixup,
+    /// A branch merged with `git merge` or `git pull`, see [`Subject::Merge`]
+    #[allow(missing_docs)]
+    Merge {
+        branches: Vec<Span>,
+        remote: Option<Span>,
+        target: Option<Span>,
+    },
+    /// A merged pull request, `id` is `None` if the subject does not mention it
+    #[allow(missing_docs)]
+    PullRequest {
+        forge: Forge,
+        id: Option<Span>,
+        description: Span,
+        source: Option<Span>,
+        target: Option<Span>,
+        remote: Option<Span>,
+        repository: Option<Span>,
+    },
+    /// Commit releasing something
+    #[allow(missing_docs)]
+    Release { version: Span, scope: Option<Span> },
+    /// Something removed
+    Remove,
+    /// Something renamed
+    Rename,
+    /// Commit created by `git-revert`
+    Revert,
+    /// A commit modifying a subtree tracked by`git-stree`.
+    #[allow(missing_docs)]
+    SubtreeCommit {
+        operation: SubtreeOperationKind,
+        subtree: Span,
+        git_ref: Span,
+    },
+    /// Just some commit
+    Simple,
+}
+
+/// Classified subject borrowing from the classified text
+///
+/// Instead of copying parts of the text, a `SubjectRef` records their byte ranges. Use
+/// [`SubjectRef::to_subject`] to get an owned [`Subject`].
+///
+/// ```rust
+/// use subject_classifier::{Subject, SubjectRef};
+///
+/// let text = "feat(ui)!: Add a button";
+/// let subject = SubjectRef::from(text);
+/// assert_eq!(subject.scope(), Some("ui"));
+/// assert_eq!(subject.scope_span(), Some(5..7));
+/// assert_eq!(subject.description(), "Add a button");
+/// assert_eq!(subject.to_subject(), Subject::from(text));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubjectRef<'a> {
+    text: &'a str,
+    kind: SubjectKind,
+    squash: Option<Span>,
+}
+
+impl<'a> From<&'a str> for SubjectRef<'a> {
+    #[inline]
+    fn from(text: &'a str) -> Self {
+        DEFAULT.classify_ref(text)
+    }
+}
+
+impl<'a> SubjectRef<'a> {
+    /// Create a classification of `text`
+    ///
+    /// All spans in `kind` must be valid ranges of `text`.
+    #[must_use]
+    #[inline]
+    pub const fn new(text: &'a str, kind: SubjectKind) -> Self {
+        Self {
+            text,
+            kind,
+            squash: None,
+        }
+    }
+
+    /// Extend a classification of the text before a squash merge reference to all of `text`
+    pub(crate) fn with_squash(self, text: &'a str, squash: Option<Span>) -> Self {
+        match squash {
+            Some(_) => Self {
+                text,
+                squash,
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    /// The classified text
+    #[must_use]
+    #[inline]
+    pub const fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The classification
+    #[must_use]
+    #[inline]
+    pub const fn kind(&self) -> &SubjectKind {
+        &self.kind
+    }
+
+    /// The text of a span
+    ///
+    /// # Panics
+    ///
+    /// Panics if `span` is not a valid range of [`SubjectRef::text`].
+    #[must_use]
+    #[inline]
+    pub fn slice(&self, span: &Span) -> &'a str {
+        &self.text[span.clone()]
+    }
+
+    /// Span of the commit subject without type, scope, squash merge reference or other markup
+    #[must_use]
+    #[inline]
+    pub fn description_span(&self) -> Span {
+        match &self.kind {
+            SubjectKind::ConventionalCommit { description, .. }
+            | SubjectKind::PullRequest { description, .. } => description.clone(),
+            _ => 0..split_squash(self.text).0.len(),
+        }
+    }
+
+    /// Commit subject without type, scope or other markup
+    #[must_use]
+    #[inline]
+    pub fn description(&self) -> &'a str {
+        self.slice(&self.description_span())
+    }
+
+    /// Span of the id of the pull request which merged the commit
+    ///
+    /// Either the id of a [`SubjectKind::PullRequest`] or the reference GitHub appends to
+    /// squash merges, e.g. `feat: Add Foo (#123)`.
+    #[must_use]
+    #[inline]
+    pub fn pull_request_span(&self) -> Option<Span> {
+        match &self.kind {
+            SubjectKind::PullRequest { id: Some(id), .. } => Some(id.clone()),
+            _ => self.squash.clone(),
+        }
+    }
+
+    /// The id of the pull request which merged the commit, see [`SubjectRef::pull_request_span`]
+    #[must_use]
+    #[inline]
+    pub fn pull_request(&self) -> Option<&'a str> {
+        self.pull_request_span().map(|span| self.slice(&span))
+    }
+
+    /// Span of the scope defined by e.g. Conventional Commit
+    #[must_use]
+    #[inline]
+    pub fn scope_span(&self) -> Option<Span> {
+        match &self.kind {
+            SubjectKind::ConventionalCommit { scope, .. } | SubjectKind::Release { scope, .. } => {
+                scope.clone()
+            }
+            SubjectKind::SubtreeCommit { subtree, .. } => Some(subtree.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the scope defined by e.g. Conventional Commit
+    #[must_use]
+    #[inline]
+    pub fn scope(&self) -> Option<&'a str> {
+        self.scope_span().map(|span| self.slice(&span))
+    }
+
+    /// The type of a conventional commit
+    #[must_use]
+    #[inline]
+    pub const fn category(&self) -> Option<&Type> {
+        match &self.kind {
+            SubjectKind::ConventionalCommit { category, .. } => Some(category),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a breaking change
+    #[must_use]
+    #[inline]
+    pub const fn breaking_change(&self) -> bool {
+        matches!(
+            self.kind,
+            SubjectKind::ConventionalCommit {
+                breaking: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Convert to an owned [`Subject`]
+    #[must_use]
+    #[inline]
+    pub fn to_subject(&self) -> Subject {
+        let text = self.text.to_owned();
+        let description = self.description().to_owned();
+        let pull_request = self.squash.as_ref().map(|s| self.slice(s).to_owned());
+        match &self.kind {
+            SubjectKind::ConventionalCommit {
+                category,
+                scope,
+                breaking,
+                ..
+            } => Subject::ConventionalCommit {
+                breaking_change: breaking.is_some(),
+                category: category.clone(),
+                scope: scope.as_ref().map(|s| self.slice(s).to_owned()),
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::Fixup => Subject::Fixup {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::Merge {
+                branches,
+                remote,
+                target,
+            } => Subject::Merge {
+                branches: branches.iter().map(|s| self.slice(s).to_owned()).collect(),
+                remote: remote.as_ref().map(|s| self.slice(s).to_owned()),
+                target: target.as_ref().map(|s| self.slice(s).to_owned()),
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::PullRequest {
+                forge,
+                id,
+                source,
+                target,
+                remote,
+                repository,
+                ..
+            } => Subject::PullRequest {
+                id: id.as_ref().map(|s| self.slice(s).to_owned()),
+                description,
+                text,
+                forge: *forge,
+                source: source.as_ref().map(|s| self.slice(s).to_owned()),
+                target: target.as_ref().map(|s| self.slice(s).to_owned()),
+                remote: remote.as_ref().map(|s| self.slice(s).to_owned()),
+                repository: repository.as_ref().map(|s| self.slice(s).to_owned()),
+            },
+            SubjectKind::Release { version, scope } => Subject::Release {
+                version: self.slice(version).to_owned(),
+                scope: scope.as_ref().map(|s| self.slice(s).to_owned()),
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::Remove => Subject::Remove {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::Rename => Subject::Rename {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::Revert => Subject::Revert {
+                description,
+                text,
+                pull_request,
+            },
+            SubjectKind::SubtreeCommit {
+                operation,
+                subtree,
+                git_ref,
+            } => {
+                let subtree = self.slice(subtree).to_owned();
+                let git_ref = self.slice(git_ref).to_owned();
+                let operation = match operation {
+                    SubtreeOperationKind::Import => SubtreeOperation::Import { subtree, git_ref },
+                    SubtreeOperationKind::Split => SubtreeOperation::Split { subtree, git_ref },
+                    SubtreeOperationKind::Update => SubtreeOperation::Update { subtree, git_ref },
+                };
+                Subject::SubtreeCommit {
+                    operation,
+                    description,
+                    text,
+                    pull_request,
+                }
+            }
+            SubjectKind::Simple => Subject::Simple {
+                description,
+                text,
+                pull_request,
+            },
+        }
+    }
+}
+
+impl From<SubjectRef<'_>> for Subject {
+    #[inline]
+    fn from(subject: SubjectRef<'_>) -> Self {
+        subject.to_subject()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Forge, SubjectKind, SubjectRef, Type};
+
+    #[test]
+    fn conventional_commit() {
+        let text = "fix(search)!: This breaks the api";
+        let result = SubjectRef::from(text);
+        assert_eq!(
+            result.kind(),
+            &SubjectKind::ConventionalCommit {
+                category: Type::Fix,
+                type_span: Some(0..3),
+                scope: Some(4..10),
+                breaking: Some(11..12),
+                description: 14..33,
+            }
+        );
+        assert_eq!(result.scope(), Some("search"));
+        assert_eq!(result.description(), "This breaks the api");
+        assert!(result.breaking_change());
+        assert_eq!(result.category(), Some(&Type::Fix));
+    }
+
+    #[test]
+    fn breaking_change_type() {
+        let text = "BREAKING CHANGE: Commits are now namedtupples";
+        let result = SubjectRef::from(text);
+        assert_eq!(
+            result.kind(),
+            &SubjectKind::ConventionalCommit {
+                category: Type::Change,
+                type_span: Some(0..15),
+                scope: None,
+                breaking: Some(0..15),
+                description: 17..45,
+            }
+        );
+    }
+
+    #[test]
+    fn heuristic() {
+        let text = "Add a button";
+        let result = SubjectRef::from(text);
+        assert_eq!(
+            result.kind(),
+            &SubjectKind::ConventionalCommit {
+                category: Type::Feat,
+                type_span: None,
+                scope: None,
+                breaking: None,
+                description: 0..12,
+            }
+        );
+    }
+
+    #[test]
+    fn pull_request() {
+        let text = "Merged PR 36587: Add Foo calibration to item type";
+        let result = SubjectRef::from(text);
+        assert_eq!(
+            result.kind(),
+            &SubjectKind::PullRequest {
+                forge: Forge::Azure,
+                id: Some(10..15),
+                description: 17..49,
+                source: None,
+                target: None,
+                remote: None,
+                repository: None,
+            }
+        );
+        assert_eq!(result.description(), "Add Foo calibration to item type");
+    }
+
+    #[test]
+    fn squash_merge() {
+        let text = "fix(api): Foo (#12)";
+        let result = SubjectRef::from(text);
+        assert_eq!(result.text(), text);
+        assert_eq!(result.description(), "Foo");
+        assert_eq!(result.pull_request_span(), Some(16..18));
+        assert_eq!(result.pull_request(), Some("12"));
+        assert_eq!(SubjectRef::from("Merge #7").pull_request(), Some("7"));
+        assert_eq!(SubjectRef::from("fix: Foo").pull_request(), None);
+        for text in ["Remove Foo (#12)", "Rename Foo (#12)", "Revert Foo (#12)"] {
+            let result = SubjectRef::from(text);
+            assert_eq!(result.description(), &text[..text.len() - 6], "{}", text);
+            assert_eq!(result.pull_request(), Some("12"), "{}", text);
+        }
+    }
+
+    #[test]
+    fn release() {
+        let result = SubjectRef::from("Release foo@v2.11.0");
+        assert_eq!(
+            result.kind(),
+            &SubjectKind::Release {
+                version: 13..19,
+                scope: Some(8..11),
+            }
+        );
+        assert_eq!(result.scope(), Some("foo"));
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Strict validation against the [Conventional Commits Standard v1.0.0](https://www.conventionalcommits.org/en/v1.0.0)
+//! grammar.
+//!
+//! Unlike [`Subject::from`](crate::Subject) which tries hard to make sense of anything, the
+//! validator reports every deviation from `type(scope)!: description`.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::classifier::DEFAULT;
+
+/// The kind of a [`Diagnostic`]
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiagnosticKind {
+    EmptySubject,
+    MissingType,
+    UnknownType,
+    UnclosedScope,
+    EmptyScope,
+    MissingColon,
+    MissingSpaceAfterColon,
+    EmptyDescription,
+    UppercaseDescription,
+    MissingBlankLine,
+}
+
+impl fmt::Display for DiagnosticKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::EmptySubject => "empty subject",
+            Self::MissingType => "missing type",
+            Self::UnknownType => "unknown type",
+            Self::UnclosedScope => "missing closing parenthesis after scope",
+            Self::EmptyScope => "empty scope",
+            Self::MissingColon => "missing colon after type",
+            Self::MissingSpaceAfterColon => "missing space after colon",
+            Self::EmptyDescription => "empty description",
+            Self::UppercaseDescription => "description starts with uppercase",
+            Self::MissingBlankLine => "missing blank line after subject",
+        };
+        f.write_str(msg)
+    }
+}
+
+/// A violation of the Conventional Commits grammar
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// What is wrong
+    pub kind: DiagnosticKind,
+    /// Byte range of the offending text, may be empty for missing text
+    pub span: Range<usize>,
+    /// Suggested replacement for the text in `span`
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}: {}", self.span.start, self.span.end, self.kind)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (replace with “{}”)", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl Diagnostic {
+    const fn new(kind: DiagnosticKind, span: Range<usize>, suggestion: Option<String>) -> Self {
+        Self {
+            kind,
+            span,
+            suggestion,
+        }
+    }
+}
+
+/// Validate a subject line against the Conventional Commits grammar
+///
+/// Returns an empty list if the subject is valid.
+///
+/// ```rust
+/// use subject_classifier::{validate, DiagnosticKind};
+///
+/// assert!(validate("feat(ui): add a button").is_empty());
+///
+/// let diagnostics = validate("feat(): Add a button");
+/// assert_eq!(diagnostics[0].kind, DiagnosticKind::EmptyScope);
+/// assert_eq!(diagnostics[1].kind, DiagnosticKind::UppercaseDescription);
+/// ```
+#[must_use]
+#[inline]
+pub fn validate(subject: &str) -> Vec<Diagnostic> {
+    DEFAULT.validate(subject)
+}
+
+/// Validate a complete commit message against the Conventional Commits grammar
+///
+/// Spans are relative to the whole message.
+#[must_use]
+#[inline]
+pub fn validate_message(message: &str) -> Vec<Diagnostic> {
+    let subject = message.lines().next().unwrap_or_default();
+    let mut result = validate(subject);
+    let rest = &message[subject.len()..];
+    let rest = rest.strip_prefix('\r').unwrap_or(rest);
+    let rest = rest.strip_prefix('\n').unwrap_or(rest);
+    if let Some(second) = rest.lines().next() {
+        if !second.trim().is_empty() {
+            let start = message.len() - rest.len();
+            result.push(Diagnostic::new(
+                DiagnosticKind::MissingBlankLine,
+                start..start,
+                Some("\n".to_owned()),
+            ));
+        }
+    }
+    result
+}
+
+/// Grammar checker shared with the configurable classifier
+pub(crate) fn validate_with<F, W>(subject: &str, is_known: F, words: W) -> Vec<Diagnostic>
+where
+    F: Fn(&str) -> bool,
+    W: FnOnce() -> Vec<String>,
+{
+    let mut result = vec![];
+    if subject.trim().is_empty() {
+        result.push(Diagnostic::new(
+            DiagnosticKind::EmptySubject,
+            0..subject.len(),
+            None,
+        ));
+        return result;
+    }
+
+    // type
+    let type_end = subject
+        .find(|c: char| matches!(c, '(' | '!' | ':' | '/') || c.is_whitespace())
+        .unwrap_or(subject.len());
+    let type_text = &subject[..type_end];
+    if type_text.is_empty() {
+        result.push(Diagnostic::new(DiagnosticKind::MissingType, 0..0, None));
+    } else if !is_known(type_text) {
+        let suggestion = closest(&type_text.to_lowercase(), &words());
+        result.push(Diagnostic::new(
+            DiagnosticKind::UnknownType,
+            0..type_end,
+            suggestion,
+        ));
+    }
+
+    // scope
+    let mut pos = type_end;
+    if subject[pos..].starts_with('(') {
+        if let Some(len) = subject[pos..].find(')') {
+            let scope = &subject[pos + 1..pos + len];
+            if scope.trim().is_empty() {
+                result.push(Diagnostic::new(
+                    DiagnosticKind::EmptyScope,
+                    pos..pos + len + 1,
+                    Some(String::new()),
+                ));
+            }
+            pos += len + 1;
+        } else {
+            let end = subject[pos..].find(':').map_or(subject.len(), |i| pos + i);
+            result.push(Diagnostic::new(
+                DiagnosticKind::UnclosedScope,
+                end..end,
+                Some(")".to_owned()),
+            ));
+            pos = end;
+        }
+    }
+
+    // breaking change marker
+    if subject[pos..].starts_with('!') {
+        pos += 1;
+    }
+
+    // separator
+    let rest = &subject[pos..];
+    if let Some(after) = rest.strip_prefix(':') {
+        pos += 1;
+        if after.starts_with(' ') {
+            pos += 1;
+        } else if !after.is_empty() {
+            result.push(Diagnostic::new(
+                DiagnosticKind::MissingSpaceAfterColon,
+                pos..pos,
+                Some(" ".to_owned()),
+            ));
+        }
+    } else {
+        let sep_len = rest.len() - rest.trim_start_matches(['/', ' ', '\t']).len();
+        result.push(Diagnostic::new(
+            DiagnosticKind::MissingColon,
+            pos..pos + sep_len,
+            Some(": ".to_owned()),
+        ));
+        pos += sep_len;
+    }
+
+    // description
+    let description = &subject[pos..];
+    match description.chars().next() {
+        None => {
+            result.push(Diagnostic::new(
+                DiagnosticKind::EmptyDescription,
+                pos..pos,
+                None,
+            ));
+        }
+        Some(c) if c.is_uppercase() => {
+            let end = pos + c.len_utf8();
+            result.push(Diagnostic::new(
+                DiagnosticKind::UppercaseDescription,
+                pos..end,
+                Some(c.to_lowercase().collect()),
+            ));
+        }
+        Some(_) => {}
+    }
+    result
+}
+
+/// Returns the word closest to `word` if it is a likely typo.
+fn closest(word: &str, words: &[String]) -> Option<String> {
+    words
+        .iter()
+        .map(|w| (levenshtein(word, w), w))
+        .filter(|(d, w)| *d <= 2 && *d < w.chars().count())
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, w)| w.clone())
+}
+
+#[allow(clippy::arithmetic_side_effects)]
+// arithmetic: distances are bounded by the word lengths
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            let next = (row[j + 1] + 1).min(row[j] + 1).min(prev + cost);
+            prev = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{validate, validate_message, Diagnostic, DiagnosticKind};
+
+    fn kinds(subject: &str) -> Vec<DiagnosticKind> {
+        validate(subject).into_iter().map(|d| d.kind).collect()
+    }
+
+    #[test]
+    fn valid() {
+        assert!(validate("feat: add a new feature").is_empty());
+        assert!(validate("fix(search)!: this breaks the api").is_empty());
+        assert!(validate("docs(readme): add xcb-util-xrm to dependencies' list").is_empty());
+    }
+
+    #[test]
+    fn missing_colon() {
+        assert_eq!(
+            validate("CHANGE Replace strncpy with memcpy"),
+            vec![
+                Diagnostic {
+                    kind: DiagnosticKind::MissingColon,
+                    span: 6..7,
+                    suggestion: Some(": ".to_owned()),
+                },
+                Diagnostic {
+                    kind: DiagnosticKind::UppercaseDescription,
+                    span: 7..8,
+                    suggestion: Some("r".to_owned()),
+                },
+            ]
+        );
+        assert_eq!(kinds("fix/some bug"), vec![DiagnosticKind::MissingColon]);
+    }
+
+    #[test]
+    fn missing_space() {
+        assert_eq!(
+            validate("fix:some bug"),
+            vec![Diagnostic {
+                kind: DiagnosticKind::MissingSpaceAfterColon,
+                span: 4..4,
+                suggestion: Some(" ".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn scope() {
+        assert_eq!(
+            validate("fix(): some bug"),
+            vec![Diagnostic {
+                kind: DiagnosticKind::EmptyScope,
+                span: 3..5,
+                suggestion: Some(String::new()),
+            }]
+        );
+        assert_eq!(
+            validate("fix(ui: some bug"),
+            vec![Diagnostic {
+                kind: DiagnosticKind::UnclosedScope,
+                span: 6..6,
+                suggestion: Some(")".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_type() {
+        assert_eq!(
+            validate("faet: some feature"),
+            vec![Diagnostic {
+                kind: DiagnosticKind::UnknownType,
+                span: 0..4,
+                suggestion: Some("feat".to_owned()),
+            }]
+        );
+        assert_eq!(
+            validate("Makefile: replace '-' in plugins_var"),
+            vec![Diagnostic {
+                kind: DiagnosticKind::UnknownType,
+                span: 0..8,
+                suggestion: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(kinds(""), vec![DiagnosticKind::EmptySubject]);
+        assert_eq!(kinds(": foo"), vec![DiagnosticKind::MissingType]);
+        assert_eq!(kinds("fix:"), vec![DiagnosticKind::EmptyDescription]);
+    }
+
+    #[test]
+    fn message() {
+        assert!(validate_message("fix: foo\n\nBody\n").is_empty());
+        assert_eq!(
+            validate_message("fix: Foo\nBody\n"),
+            vec![
+                Diagnostic {
+                    kind: DiagnosticKind::UppercaseDescription,
+                    span: 5..6,
+                    suggestion: Some("f".to_owned()),
+                },
+                Diagnostic {
+                    kind: DiagnosticKind::MissingBlankLine,
+                    span: 9..9,
+                    suggestion: Some("\n".to_owned()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,358 @@
+// Copyright (c) 2022 Bahtiar `kalkin` Gadimov <bahtiar@gadimov.de>
+//
+// This file is part of subject-classifier.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Lesser General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Lesser General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! [Semantic Versioning](https://semver.org/spec/v2.0.0.html) bump recommendations.
+//!
+//! ```rust
+//! use subject_classifier::version::{next_version, BumpPolicy, Version};
+//! use subject_classifier::Subject;
+//!
+//! // Newest first, like `git log`
+//! let log: Vec<Subject> = ["fix: Crash on startup", "feat: Dark mode", "Release v1.2.3"]
+//!     .iter()
+//!     .copied()
+//!     .map(Subject::from)
+//!     .collect();
+//! let next = next_version(&log, &BumpPolicy::default());
+//! assert_eq!(next, Some(Version::new(1, 3, 0)));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Subject, Type};
+
+/// The kind of version increment
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Bump {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// The increment required by a single subject
+    ///
+    /// Breaking changes require a major, [`Type::Feat`] a minor and [`Type::Fix`],
+    /// [`Type::Perf`] & [`Type::Security`] a patch increment.
+    #[must_use]
+    #[inline]
+    pub const fn of(subject: &Subject) -> Self {
+        match subject {
+            Subject::ConventionalCommit {
+                breaking_change: true,
+                ..
+            } => Self::Major,
+            Subject::ConventionalCommit { category, .. } => match category {
+                Type::Feat => Self::Minor,
+                Type::Fix | Type::Perf | Type::Security => Self::Patch,
+                _ => Self::None,
+            },
+            _ => Self::None,
+        }
+    }
+}
+
+/// Recommend a bump for subjects ordered newest first, like `git log` outputs them
+///
+/// Stops at the first [`Subject::Release`], so the whole history can be passed.
+#[must_use]
+#[inline]
+pub fn recommend_bump<'a, I>(subjects: I) -> Bump
+where
+    I: IntoIterator<Item = &'a Subject>,
+{
+    subjects
+        .into_iter()
+        .take_while(|s| !matches!(s, Subject::Release { .. }))
+        .map(Bump::of)
+        .max()
+        .unwrap_or(Bump::None)
+}
+
+/// Compute the next version from subjects ordered newest first
+///
+/// The current version is the one of the first [`Subject::Release`]. Returns `None` if there
+/// is no release or its version can not be parsed.
+#[must_use]
+#[inline]
+pub fn next_version<'a, I>(subjects: I, policy: &BumpPolicy) -> Option<Version>
+where
+    I: IntoIterator<Item = &'a Subject>,
+{
+    let mut bump = Bump::None;
+    for subject in subjects {
+        if let Some(version) = Version::from_release(subject) {
+            return version.ok().map(|v| v.bump(policy.adjust(&v, bump)));
+        }
+        bump = bump.max(Bump::of(subject));
+    }
+    None
+}
+
+/// Rules for versions with major version zero, which is for initial development
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BumpPolicy {
+    /// Replaces [`Bump::Major`] while the major version is zero, defaults to [`Bump::Minor`]
+    pub initial_breaking: Bump,
+    /// Replaces [`Bump::Minor`] while the major version is zero, defaults to [`Bump::Minor`]
+    pub initial_feature: Bump,
+}
+
+impl Default for BumpPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            initial_breaking: Bump::Minor,
+            initial_feature: Bump::Minor,
+        }
+    }
+}
+
+impl BumpPolicy {
+    /// Apply the rules for major version zero to `bump`
+    #[must_use]
+    #[inline]
+    pub const fn adjust(&self, version: &Version, bump: Bump) -> Bump {
+        if version.major != 0 {
+            return bump;
+        }
+        match bump {
+            Bump::Major => self.initial_breaking,
+            Bump::Minor => self.initial_feature,
+            Bump::Patch | Bump::None => bump,
+        }
+    }
+}
+
+/// Error parsing a [`Version`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseVersionError {
+    /// The version string is empty
+    Empty,
+    /// A component is not a number
+    InvalidNumber(String),
+    /// More than three components
+    TooManyComponents,
+}
+
+impl fmt::Display for ParseVersionError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty version"),
+            Self::InvalidNumber(n) => write!(f, "invalid version number “{}”", n),
+            Self::TooManyComponents => f.write_str("more than three version components"),
+        }
+    }
+}
+
+impl std::error::Error for ParseVersionError {}
+
+/// A `major.minor.patch` version
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Create a version
+    #[must_use]
+    #[inline]
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the version of a [`Subject::Release`], `None` for other subjects
+    #[must_use]
+    #[inline]
+    pub fn from_release(subject: &Subject) -> Option<Result<Self, ParseVersionError>> {
+        match subject {
+            Subject::Release { version, .. } => Some(version.parse()),
+            _ => None,
+        }
+    }
+
+    /// Increment the version
+    #[must_use]
+    #[inline]
+    #[allow(clippy::arithmetic_side_effects)]
+    pub const fn bump(self, bump: Bump) -> Self {
+        match bump {
+            Bump::None => self,
+            Bump::Patch => Self::new(self.major, self.minor, self.patch + 1),
+            Bump::Minor => Self::new(self.major, self.minor + 1, 0),
+            Bump::Major => Self::new(self.major + 1, 0, 0),
+        }
+    }
+
+    /// Compute the next version for subjects ordered newest first
+    ///
+    /// Considers all subjects up to the first [`Subject::Release`].
+    #[must_use]
+    #[inline]
+    pub fn next<'a, I>(self, subjects: I, policy: &BumpPolicy) -> Self
+    where
+        I: IntoIterator<Item = &'a Subject>,
+    {
+        self.bump(policy.adjust(&self, recommend_bump(subjects)))
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    /// Parses versions like `2.11.0`, `v2.11` or `2`, missing components are zero
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('v').unwrap_or(s).trim_end_matches('.');
+        if s.is_empty() {
+            return Err(ParseVersionError::Empty);
+        }
+        let mut numbers = [0; 3];
+        for (i, part) in s.split('.').enumerate() {
+            let number = numbers
+                .get_mut(i)
+                .ok_or(ParseVersionError::TooManyComponents)?;
+            *number = part
+                .parse()
+                .map_err(|_| ParseVersionError::InvalidNumber(part.to_owned()))?;
+        }
+        Ok(Self::new(numbers[0], numbers[1], numbers[2]))
+    }
+}
+
+impl fmt::Display for Version {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_version, recommend_bump, Bump, BumpPolicy, ParseVersionError, Version};
+    use crate::Subject;
+
+    fn subjects(log: &[&str]) -> Vec<Subject> {
+        log.iter().copied().map(Subject::from).collect()
+    }
+
+    #[test]
+    fn bump_of() {
+        for (text, bump) in [
+            ("feat!: Foo", Bump::Major),
+            ("fix(api)!: Foo", Bump::Major),
+            ("BREAKING CHANGE: Foo", Bump::Major),
+            ("feat: Foo", Bump::Minor),
+            ("Add Foo", Bump::Minor),
+            ("hotfix: Foo", Bump::Patch),
+            ("perf: Foo", Bump::Patch),
+            ("security: Foo", Bump::Patch),
+            ("docs: Foo", Bump::None),
+            ("Merge #1", Bump::None),
+        ] {
+            assert_eq!(Bump::of(&Subject::from(text)), bump, "{}", text);
+        }
+    }
+
+    #[test]
+    fn recommend() {
+        let log = subjects(&["docs: Foo", "perf: Foo", "Release v1.0.0", "feat!: Foo"]);
+        assert_eq!(recommend_bump(&log), Bump::Patch);
+        assert_eq!(recommend_bump(&log[..1]), Bump::None);
+        assert_eq!(recommend_bump(&[]), Bump::None);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("2.11.0".parse(), Ok(Version::new(2, 11, 0)));
+        assert_eq!("v2.11".parse(), Ok(Version::new(2, 11, 0)));
+        assert_eq!("2".parse(), Ok(Version::new(2, 0, 0)));
+        assert_eq!("2.0.".parse(), Ok(Version::new(2, 0, 0)));
+        assert_eq!("".parse::<Version>(), Err(ParseVersionError::Empty));
+        assert_eq!(
+            "1.2.3.4".parse::<Version>(),
+            Err(ParseVersionError::TooManyComponents)
+        );
+        assert_eq!(
+            "1..3".parse::<Version>(),
+            Err(ParseVersionError::InvalidNumber(String::new()))
+        );
+        assert_eq!(Version::new(1, 2, 3).to_string(), "1.2.3");
+    }
+
+    #[test]
+    fn from_release() {
+        for (text, version) in [
+            ("Release foo@v2.11.0", Version::new(2, 11, 0)),
+            ("Release v2.11.0", Version::new(2, 11, 0)),
+            ("Bump 0.4.2", Version::new(0, 4, 2)),
+        ] {
+            assert_eq!(
+                Version::from_release(&Subject::from(text)),
+                Some(Ok(version))
+            );
+        }
+        assert_eq!(Version::from_release(&Subject::from("feat: Foo")), None);
+    }
+
+    #[test]
+    fn next() {
+        let policy = BumpPolicy::default();
+        let log = subjects(&["feat!: Foo", "fix: Bar", "Release v1.2.3"]);
+        assert_eq!(next_version(&log, &policy), Some(Version::new(2, 0, 0)));
+        let log = subjects(&["Fix Bar", "Release v1.2.3"]);
+        assert_eq!(next_version(&log, &policy), Some(Version::new(1, 2, 4)));
+        let log = subjects(&["docs: Bar", "Release v1.2.3"]);
+        assert_eq!(next_version(&log, &policy), Some(Version::new(1, 2, 3)));
+        assert_eq!(next_version(&subjects(&["feat: Foo"]), &policy), None);
+        let log = subjects(&[
+            "feat: Foo",
+            "Bump regex from 1.5.4 to 1.5.5",
+            "Release v2.0.0",
+        ]);
+        assert_eq!(next_version(&log, &policy), Some(Version::new(2, 1, 0)));
+        assert_eq!(recommend_bump(&log), Bump::Minor);
+    }
+
+    #[test]
+    fn initial_development() {
+        let log = subjects(&["feat!: Foo", "Release v0.4.2"]);
+        let policy = BumpPolicy::default();
+        assert_eq!(next_version(&log, &policy), Some(Version::new(0, 5, 0)));
+
+        let policy = BumpPolicy {
+            initial_breaking: Bump::Major,
+            initial_feature: Bump::Patch,
+        };
+        assert_eq!(next_version(&log, &policy), Some(Version::new(1, 0, 0)));
+        assert_eq!(
+            Version::new(0, 4, 2).next(&subjects(&["feat: Foo"]), &policy),
+            Version::new(0, 4, 3)
+        );
+    }
+}